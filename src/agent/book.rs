@@ -0,0 +1,130 @@
+use std::{fs, path::Path, sync::Mutex};
+
+use crate::battle_of_lits::prelude::*;
+
+/// The number of opening plies from each finished game that are recorded into the book.
+pub const BOOK_PLIES: usize = 6;
+
+/// Serializes read-modify-write cycles on book files, so concurrent games can't interleave their updates.
+static BOOK_LOCK: Mutex<()> = Mutex::new(());
+
+/// The statistics on one move out of a book position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookMove {
+    pub id: usize,
+
+    /// The number of recorded games in which this move was played from the position.
+    pub games: u32,
+
+    /// The sum of the results of those games from the mover's perspective: +1 for a win, 0 for a draw and -1 for a loss.
+    pub score: i32,
+}
+
+/// An opening book: move statistics keyed by `Board::position_hash`.
+///
+/// The file format is one `<hash> <id> <games> <score>` line per move, with the hash in hex.
+#[derive(Clone, Debug, Default)]
+pub struct OpeningBook {
+    entries: HashMap<u64, Vec<BookMove>>,
+}
+
+impl OpeningBook {
+    /// Reads a book from the given file; a missing file is an empty book.
+    pub fn load(path: &Path) -> Result<OpeningBook> {
+        let mut book = OpeningBook::default();
+        if !path.exists() {
+            return Ok(book);
+        }
+
+        for (i, line) in fs::read_to_string(path)?.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [hash, id, games, score] = fields[..] else {
+                return Err(anyhow!("book line {} has {} fields, expected 4", i + 1, fields.len()));
+            };
+            book.add(u64::from_str_radix(hash, 16)?, BookMove { id: id.parse()?, games: games.parse()?, score: score.parse()? });
+        }
+        Ok(book)
+    }
+
+    /// Writes the book to the given file, replacing its contents.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut lines = self.entries.iter()
+            .flat_map(|(hash, moves)| moves.iter().map(move |mv| format!("{hash:016x} {} {} {}", mv.id, mv.games, mv.score)))
+            .collect::<Vec<_>>();
+        lines.sort();
+        fs::write(path, lines.join("\n") + "\n")?;
+        Ok(())
+    }
+
+    /// Gets the recorded moves out of the given position.
+    pub fn moves(&self, board: &Board) -> &[BookMove] {
+        self.entries.get(&board.position_hash()).map_or(&[], |moves| moves.as_slice())
+    }
+
+    /// Records the first `BOOK_PLIES` plies of a finished game, weighted by its result; unfinished games are ignored.
+    /// Returns whether anything was recorded.
+    pub fn record(&mut self, game: &Board) -> bool {
+        let Some(result) = game.result() else {
+            return false;
+        };
+
+        let plies = game.plies();
+        for (position, &mv) in game.positions_iter().zip(plies.iter()).take(BOOK_PLIES) {
+            let score = match result.winner() {
+                Some(winner) if winner == position.player_to_move() => 1,
+                Some(_)                                             => -1,
+                None                                                => 0,
+            };
+            self.add(position.position_hash(), BookMove { id: mv, games: 1, score });
+        }
+        !plies.is_empty()
+    }
+
+    /// Records a finished game into the book file at the given path, creating the file if needed.
+    pub fn record_to_file(path: &Path, game: &Board) -> Result<bool> {
+        let _guard = BOOK_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut book = OpeningBook::load(path)?;
+        if !book.record(game) {
+            return Ok(false);
+        }
+        book.save(path)?;
+        Ok(true)
+    }
+
+    fn add(&mut self, hash: u64, mv: BookMove) {
+        let moves = self.entries.entry(hash).or_default();
+        match moves.iter_mut().find(|existing| existing.id == mv.id) {
+            Some(existing) => {
+                existing.games += mv.games;
+                existing.score += mv.score;
+            },
+            None => moves.push(mv),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::battle_of_lits::testing::*;
+
+    use super::*;
+
+    #[test]
+    fn recorded_games_accumulate() {
+        let game = board(&format!("{SETUP}; {FINISHED}"));
+        let mut book = OpeningBook::default();
+        assert!(!book.record(&board(SETUP)));
+        assert!(book.record(&game));
+        assert!(book.record(&game));
+
+        let setup = board(SETUP);
+        let first = piece("L[27,26,25,35]");
+        assert_eq!(book.moves(&setup), [BookMove { id: first, games: 2, score: 2 }]); // X won, and X moved first
+
+        let path = std::env::temp_dir().join(format!("blits-book-{}.txt", std::process::id()));
+        book.save(&path).unwrap();
+        let reloaded = OpeningBook::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.moves(&setup), book.moves(&setup));
+    }
+}
@@ -1,11 +1,14 @@
+use std::marker::PhantomData;
+
 use super::LITSGame;
+use crate::battle_of_lits::prelude::PieceMap;
 
 #[derive(Clone, Copy, Debug, Default)]
 /// The BLITS evaluator for nonterminal states.
-pub struct Evaluator;
+pub struct Evaluator<'a>(PhantomData<&'a PieceMap>);
 
-impl minimax::Evaluator for Evaluator {
-    type G = LITSGame;
+impl<'a> minimax::Evaluator for Evaluator<'a> {
+    type G = LITSGame<'a>;
 
     fn evaluate(&self, state: &<Self::G as minimax::Game>::S) -> minimax::Evaluation {
         state.effective_score()
@@ -1,10 +1,13 @@
 
+use std::marker::PhantomData;
+
 use crate::battle_of_lits::prelude::*;
 
-pub struct LITSGame;
+/// The game of LITS, played on boards that borrow a piecemap for `'a`.
+pub struct LITSGame<'a>(PhantomData<&'a PieceMap>);
 
-impl minimax::Game for LITSGame {
-    type S = Board<'static>;
+impl<'a> minimax::Game for LITSGame<'a> {
+    type S = Board<'a>;
     type M = usize;
 
     fn apply(state: &mut Self::S, m: Self::M) -> Option<Self::S> {
@@ -57,6 +60,6 @@ impl minimax::Game for LITSGame {
     }
 
     fn zobrist_hash(state: &Self::S) -> u64 {
-        state.zobrist()
+        state.transposition_key()
     }
 }
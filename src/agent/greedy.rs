@@ -0,0 +1,92 @@
+use minimax::Game;
+use rayon::prelude::*;
+
+use super::LITSGame;
+use crate::battle_of_lits::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default)]
+/// A searchless policy that plays the move whose resulting position has the best static evaluation.
+/// 
+/// Ties are broken towards the earliest move in generation order, so the choice is deterministic.
+pub struct GreedyStrategy {
+    parallel_movegen: bool,
+}
+
+impl GreedyStrategy {
+    pub fn new() -> GreedyStrategy {
+        GreedyStrategy::default()
+    }
+
+    /// Evaluates the root children on the rayon pool; the chosen move is the same as the serial policy's.
+    pub fn with_parallel_movegen(mut self) -> GreedyStrategy {
+        self.parallel_movegen = true;
+        self
+    }
+}
+
+impl<'a> minimax::Strategy<LITSGame<'a>> for GreedyStrategy {
+    fn choose_move(&mut self, state: &Board<'a>) -> Option<usize> {
+        let values = if self.parallel_movegen { par_static_root_values(state) } else { static_root_values(state) };
+        values
+            .into_iter()
+            .fold(None, |best: Option<(usize, i16)>, (mv, value)| {
+                if best.is_none_or(|(_, v)| value > v) { Some((mv, value)) } else { best }
+            })
+            .map(|(mv, _)| mv)
+    }
+}
+
+/// Evaluates each legal move in generation order by the static evaluation of the resulting position, from the
+/// perspective of the player to move.
+pub(crate) fn static_root_values(state: &Board) -> Vec<(usize, i16)> {
+    let mut moves = vec![];
+    LITSGame::generate_moves(state, &mut moves);
+
+    moves.into_iter().map(|mv| (mv, static_child_value(state, mv))).collect()
+}
+
+/// As `static_root_values`, but evaluates the children in parallel; rayon keeps the results in generation order.
+pub(crate) fn par_static_root_values(state: &Board) -> Vec<(usize, i16)> {
+    let mut moves = vec![];
+    LITSGame::generate_moves(state, &mut moves);
+
+    moves.into_par_iter().map(|mv| (mv, static_child_value(state, mv))).collect()
+}
+
+fn static_child_value(state: &Board, mv: usize) -> i16 {
+    let mut child = state.clone();
+    match mv {
+        NULL_MOVE => child.pass_unchecked_engine(),
+        _         => child.play_unchecked_engine(mv),
+    };
+    -child.effective_score() // the child is evaluated from the opponent's perspective
+}
+
+#[cfg(test)]
+mod tests {
+    use minimax::Strategy;
+
+    use crate::battle_of_lits::testing::*;
+    use crate::prelude::SetOps;
+
+    use super::GreedyStrategy;
+
+    #[test]
+    fn greedy_is_legal_and_deterministic() {
+        let board = board(&format!("{SETUP}; I[96,97,98,99]; swap"));
+        let mv = GreedyStrategy::new().choose_move(&board).unwrap();
+
+        assert!(board.valid_moves_set().contains(mv));
+        assert_eq!(GreedyStrategy::new().choose_move(&board), Some(mv));
+    }
+
+    #[test]
+    fn parallel_movegen_matches_serial() {
+        let board = board(SETUP);
+        let serial = GreedyStrategy::new().choose_move(&board);
+        let parallel = GreedyStrategy::new().with_parallel_movegen().choose_move(&board);
+
+        assert!(serial.is_some());
+        assert_eq!(parallel, serial);
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+/// The bound on a single search, as last configured on the agent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchLimit {
+    Depth(u8),
+    Time(Duration),
+}
+
+/// A report on a completed search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchInfo {
+    /// The depth searched; for a search bounded by time rather than depth, the length of the principal variation
+    /// instead, since the strategies don't report how deep they got.
+    pub depth: u8,
+
+    /// The best move found so far.
+    pub best_move: usize,
+
+    /// The static evaluation at the end of the principal variation, from the searching player's perspective.
+    pub score: i16,
+
+    /// The principal variation, starting with the best move.
+    pub pv: Vec<usize>,
+}
+
+/// Everything known about the latest completed `generate_move`, for diagnostics in a single query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchSummary {
+    /// The report on the final iteration, including the best move, its principal variation and the root evaluation.
+    pub info: SearchInfo,
+
+    /// The wall-clock time the whole search took.
+    pub elapsed: Duration,
+}
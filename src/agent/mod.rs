@@ -1,25 +1,40 @@
+mod book;
 mod evaluator;
 mod game;
+mod greedy;
+mod info;
+mod openings;
+mod random;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::battle_of_lits::prelude::*;
 
+pub use book::{BookMove, OpeningBook, BOOK_PLIES};
 pub use evaluator::Evaluator;
 pub use game::LITSGame;
+pub use greedy::GreedyStrategy;
+pub use info::{SearchInfo, SearchLimit, SearchSummary};
+pub use openings::SelfplayOpenings;
+pub use random::RandomStrategy;
 use minimax::{strategies::mcts, IterativeOptions, MCTSOptions, ParallelOptions, Strategy};
 
 /// An implementation of the actual blits engine.
-pub struct BLITSAgent {
-    board: Board<'static>,
-    strategy: Box<dyn Strategy<LITSGame>>,
-    piecemap: &'static PieceMap,
+pub struct BLITSAgent<'a> {
+    board: Board<'a>,
+    strategy: Box<dyn Strategy<LITSGame<'a>> + 'a>,
+    piecemap: &'a PieceMap,
     past: Vec<usize>,
-    past_boards: Vec<Board<'static>>,
-    future: Vec<usize>
+    past_boards: Vec<Board<'a>>,
+    future: Vec<usize>,
+    board_options: BoardOptions,
+    limit: SearchLimit,
+    info_callback: Option<Box<dyn FnMut(SearchInfo)>>,
+    last_summary: Option<SearchSummary>,
+    config: AgentConfig,
 }
 
-impl BLITSAgent {
+impl<'a> BLITSAgent<'a> {
     /// Creates a new board. If a symbol map is provided, initializes that board, otherwise generates one.
     /// 
     /// This method does _NOT_ handle the entire game string. That's because any user of the agent needs to
@@ -27,9 +42,10 @@ impl BLITSAgent {
     /// 1. initializing each board with the setup string
     /// 2. playing each move in order to allow the agents to build their linear histories
     pub fn new(&mut self, setup_str: Option<SetupString>) {
-        self.board = Board::new(setup_str.map(|v| v.grid), self.piecemap);
+        self.board = Board::new(setup_str.map(|v| v.grid), self.piecemap).with_options(self.board_options);
         [self.past, self.future] = [vec![], vec![]];
         self.past_boards = vec![];
+        self.last_summary = None;
     }
 
     /// Plays a move on the board if it is legal. If the move is a redo, then just redo it and maintain the future history.
@@ -76,10 +92,28 @@ impl BLITSAgent {
         }
     }
 
+    /// Swaps if the static evaluation says it helps the player to move, returning whether the swap was played.
+    /// 
+    /// This encapsulates the first-reply decision for simple bots that always swap when it's beneficial.
+    pub fn auto_swap_decision(&mut self) -> bool {
+        if self.board.can_swap() && self.board.swap_value() > 0 {
+            self.swap().is_ok()
+        } else {
+            false
+        }
+    }
+
     /// Undoes a move on the board if it is legal.
     pub fn undo_move(&mut self) -> Result<usize> {
-        if let Some(mv) = self.past.pop() {
-            self.board = self.past_boards.pop().unwrap();
+        if let Some(&mv) = self.past.last() {
+            if mv == NULL_MOVE {
+                self.board.unswap()?; // the swap is its own inverse, so re-negate rather than restore
+            }
+            self.past.pop();
+            let previous = self.past_boards.pop().unwrap();
+            if mv != NULL_MOVE {
+                self.board = previous;
+            }
             self.future.push(mv);
             Ok(mv)
         } else {
@@ -89,9 +123,78 @@ impl BLITSAgent {
 
     /// Generates the best move in the current position.
     pub fn generate_move(&mut self) -> Result<usize> {
-        self.strategy.choose_move(&self.board).ok_or(
-            anyhow!("failed to generate a move")
-        )
+        let start = Instant::now();
+        let info = self.search()?;
+        let mv = info.best_move;
+        self.last_summary = Some(SearchSummary { info, elapsed: start.elapsed() });
+        Ok(mv)
+    }
+
+    /// Searches the given position for the given time, then puts the game, its history and the search limit back as
+    /// they were. Only what the search itself warmed up (its table, threads and caches) is kept.
+    pub fn warm_up(&mut self, board: &Board<'a>, duration: Duration) -> Result<()> {
+        let game = std::mem::replace(&mut self.board, board.clone());
+        let (past, past_boards, future) = (self.past.clone(), self.past_boards.clone(), self.future.clone());
+        let (limit, last_summary) = (self.limit, self.last_summary.take());
+
+        self.set_max_time(duration);
+        let result = self.generate_move();
+
+        self.board = game;
+        [self.past, self.future] = [past, future];
+        self.past_boards = past_boards;
+        self.last_summary = last_summary;
+        match limit {
+            SearchLimit::Depth(depth) => self.set_max_depth(depth),
+            SearchLimit::Time(time)   => self.set_max_time(time),
+        };
+        result.map(|_| ())
+    }
+
+    /// Gets the summary of the latest search in this game, if there has been one.
+    pub fn last_search_summary(&self) -> Option<SearchSummary> {
+        self.last_summary.clone()
+    }
+
+    /// Runs a single search and reports on it, invoking the info callback (if any) once it completes. The strategies
+    /// don't report their intermediate iterations, so only the final one is reported.
+    fn search(&mut self) -> Result<SearchInfo> {
+        let mv = self.strategy.choose_move(&self.board).ok_or(anyhow!("failed to generate a move"))?;
+        let depth = match self.limit {
+            SearchLimit::Depth(depth) => depth,
+            SearchLimit::Time(_)      => 0,
+        };
+
+        let info = self.search_info(depth, mv);
+        if let Some(callback) = &mut self.info_callback {
+            callback(info.clone());
+        }
+        Ok(info)
+    }
+
+    /// Summarizes the latest completed search whose best move is `mv`. A depth of 0 stands for a timed search, whose
+    /// depth the strategies don't report, so the length of the principal variation is given instead.
+    fn search_info(&self, depth: u8, mv: usize) -> SearchInfo {
+        let mut pv = self.strategy.principal_variation();
+        if pv.first() != Some(&mv) {
+            pv = vec![mv];
+        }
+
+        let mut board = self.board.clone();
+        for &mv in &pv {
+            match mv {
+                NULL_MOVE => board.pass_unchecked_engine(),
+                _         => board.play_unchecked_engine(mv),
+            };
+        }
+        let perspective = if pv.len() % 2 == 0 { 1 } else { -1 };
+
+        SearchInfo {
+            depth: if depth == 0 { pv.len() as u8 } else { depth },
+            best_move: mv,
+            score: perspective * board.effective_score(),
+            pv,
+        }
     }
 
     /// Gets the principal variation.
@@ -99,33 +202,66 @@ impl BLITSAgent {
         self.strategy.principal_variation()
     }
 
+    /// Sets a callback that receives a report on each search `generate_move` completes.
+    pub fn set_info_callback(&mut self, cb: Box<dyn FnMut(SearchInfo)>) {
+        self.info_callback = Some(cb);
+    }
+
+    /// Rebuilds the search strategy from a new configuration, keeping the game and the current search limit.
+    /// 
+    /// Replacing the strategy drops the old one, which stops any background pondering it was doing.
+    pub fn reconfigure(&mut self, config: &AgentConfig) {
+        self.config = config.clone();
+        self.strategy = config.strategy();
+        match self.limit {
+            SearchLimit::Depth(depth) => self.set_max_depth(depth),
+            SearchLimit::Time(time)   => self.set_max_time(time),
+        };
+    }
+
+    /// Discards everything the search has learned, such as its transposition table, by rebuilding the strategy from
+    /// the current configuration; this keeps stale entries from one position from misleading the next.
+    pub fn clear_tt(&mut self) {
+        self.reconfigure(&self.config.clone());
+    }
+
     /// Configures the max depth on the search.
     pub fn set_max_depth(&mut self, depth: u8) {
+        self.limit = SearchLimit::Depth(depth);
         self.strategy.set_max_depth(depth);
     }
 
     /// Configures the timeout on the search.
     pub fn set_max_time(&mut self, time: Duration) {
+        self.limit = SearchLimit::Time(time);
         self.strategy.set_timeout(time);
     }
 
-    pub fn with_board(&mut self, board: &Board<'static>) {
+    pub fn with_board(&mut self, board: &Board<'a>) {
         self.board = board.clone();
         [self.past, self.future] = [vec![], vec![]];
         self.past_boards = vec![];
+        self.last_summary = None;
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
 pub enum WhichStrategy {
+    Greedy,
     MCTS,
-    Negamax
+    Negamax,
+    Random
 }
 
+#[derive(Clone)]
 pub struct AgentConfig {
     pub search_opts: minimax::IterativeOptions,
     pub parallel_opts: minimax::ParallelOptions,
     pub mcts_opts: minimax::MCTSOptions,
     pub selected: WhichStrategy,
+    pub board_opts: BoardOptions,
+    pub parallel_movegen: bool,
+    pub random_seed: u64,
 }
 
 impl Default for AgentConfig {
@@ -139,7 +275,10 @@ impl Default for AgentConfig {
                 .with_num_threads(std::thread::available_parallelism().map_or(1, |v| v.into())),
             mcts_opts: MCTSOptions::default()
                 .with_num_threads(std::thread::available_parallelism().map_or(1, |v| v.into())),
-            selected: WhichStrategy::Negamax
+            selected: WhichStrategy::Negamax,
+            board_opts: BoardOptions::default(),
+            parallel_movegen: false,
+            random_seed: 0,
         }
     }
 }
@@ -150,27 +289,173 @@ impl AgentConfig {
         AgentConfig::default()
     }
 
+    /// Produces the selected search strategy.
+    pub fn strategy<'a>(&self) -> Box<dyn Strategy<LITSGame<'a>> + 'a> {
+        match self.selected {
+            WhichStrategy::Greedy if self.parallel_movegen => Box::new(GreedyStrategy::new().with_parallel_movegen()),
+            WhichStrategy::Greedy  => Box::new(GreedyStrategy::new()),
+            WhichStrategy::MCTS    => Box::new(mcts::MonteCarloTreeSearch::new(self.mcts_opts.clone())),
+            WhichStrategy::Negamax => Box::new(minimax::ParallelSearch::new(Evaluator::default(), self.search_opts, self.parallel_opts)),
+            WhichStrategy::Random  => Box::new(RandomStrategy::new(self.random_seed)),
+        }
+    }
+
     /// Produces an agent.
-    pub fn get_agent(&self, piecemap: &'static PieceMap) -> BLITSAgent {
-        let mut agent = match self.selected {
-            WhichStrategy::Negamax => BLITSAgent { 
-                board: Board::new(None, piecemap), 
-                strategy: Box::new(minimax::ParallelSearch::new(Evaluator::default(), self.search_opts, self.parallel_opts)),
-                piecemap,
-                past: vec![],
-                past_boards: vec![],
-                future: vec![] 
-            },
-            WhichStrategy::MCTS => BLITSAgent { 
-                board: Board::new(None, piecemap), 
-                strategy: Box::new(mcts::MonteCarloTreeSearch::new(self.mcts_opts.clone())), 
-                piecemap, 
-                past: vec![], 
-                past_boards: vec![], 
-                future: vec![]
-            }
+    pub fn get_agent<'a>(&self, piecemap: &'a PieceMap) -> BLITSAgent<'a> {
+        let mut agent = BLITSAgent { 
+            board: Board::new(None, piecemap).with_options(self.board_opts), 
+            strategy: self.strategy(),
+            piecemap,
+            past: vec![],
+            past_boards: vec![],
+            future: vec![],
+            board_options: self.board_opts,
+            limit: SearchLimit::Depth(20),
+            info_callback: None,
+            last_summary: None,
+            config: self.clone(),
         };
         agent.set_max_depth(20);
         agent
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::battle_of_lits::testing::*;
+
+    use super::*;
+
+    /// A single-threaded agent with a small table, so tests don't contend for cores or memory.
+    fn agent() -> BLITSAgent<'static> {
+        agent_with(BoardOptions::default())
+    }
+
+    fn agent_with(board_opts: BoardOptions) -> BLITSAgent<'static> {
+        let mut config = AgentConfig::default();
+        config.search_opts = config.search_opts.with_table_byte_size(1 << 20);
+        config.parallel_opts = config.parallel_opts.with_num_threads(1);
+        config.board_opts = board_opts;
+        config.get_agent(piecemap())
+    }
+
+    #[test]
+    fn auto_swap_decision() {
+        let mut agent = agent();
+
+        agent.with_board(&board(&format!("{SETUP}; I[96,97,98,99]")));
+        assert!(agent.auto_swap_decision());
+        assert_eq!(agent.past, vec![NULL_MOVE]);
+
+        agent.with_board(&board(&format!("{SETUP}; L[00,01,02,10]")));
+        assert!(!agent.auto_swap_decision());
+        assert!(agent.past.is_empty());
+    }
+
+    #[test]
+    fn canonical_openings_preserve_the_best_move() {
+        let setup = TRANSPOSED.parse::<SetupString>().unwrap();
+        let value = |mv: usize| {
+            let mut board = board(TRANSPOSED);
+            board.play(mv).unwrap();
+            board.effective_score()
+        };
+
+        let [mut full, mut canonical] = [agent(), agent_with(BoardOptions::new().with_canonical_openings())];
+        let mut moves = [0; 2];
+        for (i, agent) in [&mut full, &mut canonical].into_iter().enumerate() {
+            agent.new(Some(setup.clone()));
+            agent.set_max_depth(1);
+            moves[i] = agent.generate_move().unwrap();
+        }
+
+        // the root branching factor (and so the search) roughly halves, without changing the value of the best move
+        let mut generated = [vec![], vec![]];
+        full.board.valid_moves(&mut generated[0]);
+        canonical.board.valid_moves(&mut generated[1]);
+        assert!(2 * generated[1].len() <= generated[0].len() + 50);
+        assert!(canonical.board.canonical_opening_moves().contains(moves[1]));
+        assert_eq!(value(moves[0]), value(moves[1]));
+    }
+
+    #[test]
+    fn info_callback_reports_each_search() {
+        let infos = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let sink = infos.clone();
+
+        let mut agent = agent();
+        agent.with_board(&board(&format!("{SETUP}; I[96,97,98,99]; swap")));
+        agent.set_info_callback(Box::new(move |info| sink.borrow_mut().push(info)));
+        agent.set_max_depth(2);
+        let mv = agent.generate_move().unwrap();
+
+        let infos = infos.borrow();
+        assert_eq!(infos.iter().map(|info| info.depth).collect::<Vec<_>>(), [2]);
+        assert_eq!(infos.last().unwrap().best_move, mv);
+        assert_eq!(agent.last_search_summary().unwrap().info, *infos.last().unwrap());
+    }
+
+    #[test]
+    fn warming_up_leaves_the_game_alone() {
+        let mut agent = agent();
+        agent.with_board(&board(SETUP));
+        agent.play_move(piece("I[38,37,36,35]")).unwrap();
+        agent.set_max_depth(3);
+
+        agent.warm_up(&board(&format!("{SETUP}; I[96,97,98,99]")), Duration::from_millis(20)).unwrap();
+        assert_eq!(agent.board.notate(), board(&format!("{SETUP}; I[38,37,36,35]")).notate());
+        assert_eq!((agent.past.len(), agent.past_boards.len()), (1, 1));
+        assert_eq!(agent.limit, SearchLimit::Depth(3));
+        assert_eq!(agent.last_search_summary(), None);
+    }
+
+    #[test]
+    fn search_summary_follows_the_latest_search() {
+        let mut agent = agent();
+        agent.with_board(&board(&format!("{SETUP}; I[96,97,98,99]; swap")));
+        assert_eq!(agent.last_search_summary(), None);
+
+        agent.set_max_depth(2);
+        let mv = agent.generate_move().unwrap();
+        let summary = agent.last_search_summary().unwrap();
+        assert_eq!((summary.info.depth, summary.info.best_move), (2, mv));
+        assert_eq!(summary.info.pv.first(), Some(&mv));
+
+        agent.with_board(&board(SETUP));
+        assert_eq!(agent.last_search_summary(), None);
+    }
+
+    #[test]
+    fn undoing_the_swap_unswaps() {
+        let mut agent = agent();
+        let before = board(&format!("{SETUP}; I[96,97,98,99]"));
+        agent.with_board(&before);
+
+        agent.swap().unwrap();
+        assert_eq!(agent.undo_move().unwrap(), NULL_MOVE);
+        assert_eq!(agent.board.notate(), before.notate());
+        assert_eq!(agent.board.zobrist(), before.zobrist());
+
+        agent.redo_move().unwrap();
+        assert!(agent.board.notate().ends_with("swap"));
+    }
+
+    #[test]
+    fn random_agent_plays_legal_moves() {
+        let config = AgentConfig { selected: WhichStrategy::Random, random_seed: 7, ..AgentConfig::default() };
+        let mut agent = config.get_agent(piecemap());
+        agent.with_board(&board(SETUP));
+
+        let mut first = None;
+        while !agent.board.is_terminal() {
+            let mv = agent.generate_move().unwrap();
+            assert!(agent.board.valid_moves_set().contains(mv));
+            first.get_or_insert(mv);
+            agent.play_move(mv).unwrap();
+        }
+
+        let mut replay = config.get_agent(piecemap());
+        replay.with_board(&board(SETUP));
+        assert_eq!(replay.generate_move().ok(), first);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{fs, path::Path};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::battle_of_lits::prelude::*;
+
+/// A curated list of opening gamestrings that self-play games start from instead of a fresh board.
+///
+/// The file format is one gamestring per line; blank lines are ignored, and lines that don't parse or don't replay to a
+/// legal position are skipped with a warning.
+pub struct SelfplayOpenings {
+    openings: Vec<GameString>,
+    next: usize,
+    rng: Option<StdRng>,
+}
+
+impl SelfplayOpenings {
+    /// Reads and validates the openings in the given file. They are handed out round-robin, or uniformly at random if
+    /// a seed is given.
+    pub fn load(path: &Path, piecemap: &PieceMap, seed: Option<u64>) -> Result<SelfplayOpenings> {
+        let mut openings = vec![];
+        for (i, line) in fs::read_to_string(path)?.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            match SelfplayOpenings::validate(line.trim(), piecemap) {
+                Ok(gamestr) => openings.push(gamestr),
+                Err(e)      => log::warn!("skipping opening on line {}: {e:#}", i + 1),
+            }
+        }
+
+        if openings.is_empty() {
+            return Err(anyhow!("no valid openings in {}", path.display()));
+        }
+        Ok(SelfplayOpenings { openings, next: 0, rng: seed.map(StdRng::seed_from_u64) })
+    }
+
+    /// Gets the number of usable openings.
+    pub fn count(&self) -> usize {
+        self.openings.len()
+    }
+
+    /// Picks the opening to start the next game from.
+    pub fn next_opening(&mut self) -> GameString {
+        let index = match &mut self.rng {
+            Some(rng) => rng.random_range(0..self.openings.len()),
+            None      => {
+                let index = self.next;
+                self.next = (self.next + 1) % self.openings.len();
+                index
+            }
+        };
+        self.openings[index].clone()
+    }
+
+    /// Parses an opening and replays it on a scratch board, rejecting it if the resulting position is illegal.
+    fn validate(line: &str, piecemap: &PieceMap) -> Result<GameString> {
+        let gamestr = line.parse::<GameString>()?;
+        Board::from_gamestring(&gamestr, piecemap)?.validate_position()?;
+        Ok(gamestr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::battle_of_lits::testing::*;
+
+    use super::*;
+
+    #[test]
+    fn openings_are_validated_and_all_used() {
+        let first = format!("{SETUP}; I[38,37,36,35]");
+        let second = format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]; L[14,15,16,26]");
+        let asymmetric = format!("X{}", &BLANK[1..]);
+
+        let path = std::env::temp_dir().join(format!("blits-openings-{}.txt", std::process::id()));
+        fs::write(&path, format!("{first}\nnot a gamestring\n\n{asymmetric}\n{second}\n")).unwrap();
+        let mut round_robin = SelfplayOpenings::load(&path, piecemap(), None).unwrap();
+        let mut seeded = SelfplayOpenings::load(&path, piecemap(), Some(7)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let plies = |gamestr: GameString| gamestr.moves.len();
+        assert_eq!(round_robin.count(), 2);
+        assert_eq!((0..4).map(|_| plies(round_robin.next_opening())).collect::<Vec<_>>(), vec![1, 3, 1, 3]);
+
+        let drawn = (0..20).map(|_| plies(seeded.next_opening())).collect::<BTreeSet<_>>();
+        assert_eq!(drawn, BTreeSet::from([1, 3]));
+    }
+}
@@ -0,0 +1,23 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::LITSGame;
+use crate::battle_of_lits::prelude::*;
+
+/// A baseline policy that plays a uniformly random legal move; any real strategy should beat it decisively.
+/// 
+/// The generator is seeded, so a given seed replays the same game against a deterministic opponent.
+pub struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> RandomStrategy {
+        RandomStrategy { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl<'a> minimax::Strategy<LITSGame<'a>> for RandomStrategy {
+    fn choose_move(&mut self, state: &Board<'a>) -> Option<usize> {
+        state.valid_moves_set().random(&mut self.rng)
+    }
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+/// The length of the compressed setup that leads a compact encoding.
+const COMPACT_SETUP_LEN: usize = 20;
+
+impl<'a> Board<'a> {
+    /// Encodes the game as its 20-character compressed setup followed by each ply's piece id as a LEB128 varint (the
+    /// swap is the null move); ids fit in 11 bits, so each ply takes at most two bytes.
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let mut bytes = crate::battle_of_lits::notation::_compress_grid(&self.cells, self.swapped).into_bytes();
+        for mut id in self.plies() {
+            while id >= 0x80 {
+                bytes.push((id as u8 & 0x7f) | 0x80);
+                id >>= 7;
+            }
+            bytes.push(id as u8);
+        }
+        bytes
+    }
+
+    /// Decodes a game from `Board::to_compact_bytes`, checking that every ply is legal.
+    pub fn from_compact_bytes(bytes: &[u8], piecemap: &'a PieceMap) -> Result<Board<'a>> {
+        if bytes.len() < COMPACT_SETUP_LEN {
+            return Err(anyhow!("compact game is {} bytes, shorter than its setup", bytes.len()));
+        }
+        let (setup, mut rest) = bytes.split_at(COMPACT_SETUP_LEN);
+        let setup = std::str::from_utf8(setup)?.parse::<SetupString>()?;
+        let mut board = Board::new(Some(setup.grid), piecemap);
+
+        while !rest.is_empty() {
+            let mut id = 0usize;
+            let mut shift = 0;
+            loop {
+                let Some((&byte, tail)) = rest.split_first() else {
+                    return Err(anyhow!("compact game ends partway through a piece id"));
+                };
+                rest = tail;
+                id |= ((byte & 0x7f) as usize) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                if shift >= 14 {
+                    return Err(anyhow!("piece id runs past two bytes"));
+                }
+            }
+
+            if id > NULL_MOVE {
+                return Err(anyhow!("piece id {id} is out of range"));
+            }
+            match id {
+                NULL_MOVE => board.pass()?,
+                _         => board.play(id)?,
+            };
+        }
+        Ok(board)
+    }
+}
@@ -7,11 +7,7 @@ impl<'a> Board<'a> {
         if coord.in_bounds() {
             Ok(self.cells.0[coord.row][coord.col])
         } else {
-            Err(anyhow!(
-                "invalid coordinate ({:02}, {:02})",
-                coord.row,
-                coord.col
-            ))
+            Err(anyhow!("invalid coordinate {}", OffsetCoord::from(coord)))
         }
     }
 }
@@ -40,8 +36,9 @@ impl<'a> Board<'a> {
             *r = r.with_cell(cell);
             [prev, r.clone()]
         };
-        if self.get_unchecked(coord).lits_value().is_none() {  // add this symbol to the score if the new symbol is uncovered
+        if self.get_unchecked(coord).lits_value().is_none() {  // swap the old symbol for the new one in the score if uncovered
             self.score += cell.map_or(0, |v| v.perspective()); // if covered, do nothing, because the next operation that uncovers it will fix it
+            self.score -= prev.cell_value().map_or(0, |v| v.perspective());
         }
         self.zobrist_hash ^= Board::cell_hash(coord.row, coord.col, prev);
         self.zobrist_hash ^= Board::cell_hash(coord.row, coord.col, new);
@@ -54,7 +51,7 @@ impl<'a> Board<'a> {
         coord: &Coord,
         lits: Option<Tile>,
     ) -> &mut Self {
-        let [cur, _prev] = {
+        let [cur, prev] = {
             let r = self.get_mut_unchecked(coord);
             let prev = r.lits_value();
             *r = r.with_lits(lits);
@@ -64,7 +61,14 @@ impl<'a> Board<'a> {
             Some(_) => -1, // setting a tile; remove this symbol from score
             None    =>  1, // unsetting a tile; add this symbol to score
         };
-        // we ended up never using the edge counter...
+        if cur != prev {
+            if prev.is_some() {
+                self.edge_mask.update_unchecked(coord, None, prev);
+            }
+            if cur.is_some() {
+                self.edge_mask.update_unchecked(coord, cur, None);
+            }
+        }
         self.foursquare_mask.update_unchecked(coord, cur);
         self
     }
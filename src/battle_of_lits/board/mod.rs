@@ -1,10 +1,15 @@
 pub(crate) mod board_cell;
+pub(crate) mod compact;
 pub(crate) mod foursquare;
 pub(crate) mod indexing;
 pub(crate) mod moves;
 pub(crate) mod neighbours;
+pub(crate) mod options;
+pub(crate) mod outcome;
+pub(crate) mod phase;
 pub(crate) mod pretty;
 pub(crate) mod reachability;
+pub(crate) mod result;
 pub(crate) mod scores;
 pub(crate) mod zobrist;
 
@@ -13,6 +18,10 @@ use super::prelude::*;
 use board_cell::BoardCell;
 use foursquare::FoursquareCounter;
 use neighbours::EdgeCounter;
+pub use options::BoardOptions;
+pub use outcome::PlayOutcome;
+pub use phase::Phase;
+pub use result::GameResult;
 
 
 /// The grid of cells on a LITS board.
@@ -20,6 +29,13 @@ use neighbours::EdgeCounter;
 pub struct Grid(pub [[BoardCell; BOARD_SIZE]; BOARD_SIZE]);
 
 impl Grid {
+    /// Extracts the setup symbols from this grid, dropping any tiles and undoing the negation of a swap.
+    pub fn setup(&self, was_swapped: bool) -> Grid {
+        Grid(self.0.map(|row| {
+            row.map(|cell| BoardCell::default().with_cell(cell.cell_value().map(|v| if was_swapped { -v } else { v })))
+        }))
+    }
+
     pub fn notate(&self, was_swapped: bool) -> String {
         self.0.map(|row| { 
             row.map(|cell| { 
@@ -76,12 +92,18 @@ pub struct Board<'a> {
     /// Cached set of cells protected by foursquare. We deliberately include covered cells here.
     protected: CoordSet,
 
+    /// Cached `protected` minus `cover`, which both movegen and the evaluator would otherwise recompute on every call.
+    protected_uncovered: CoordSet,
+
     /// Set of cells that are implicated in scoring.
     symbols: CoordSet,
 
     /// The number of pieces remaining in each type.
     piece_bag: [usize; 4],
 
+    /// Engine-side behaviours, and any rule variants this game is played under.
+    options: BoardOptions,
+
     /// A reference to the built piecemap, so we can avoid an RWLock and threadsafe mechanisms that add overhead.
     pub piecemap: &'a PieceMap,
 
@@ -104,11 +126,77 @@ impl<'a> Board<'a> {
         self.swapped == false && self.history.len() == 1
     }
 
+    /// Determines one representative opening move per symmetry class of the setup; that is, under every board transform
+    /// that leaves the symbols unchanged, only the smallest piece id in each orbit is kept.
+    /// 
+    /// Since Os are the 180-degree image of Xs, real setups are rarely symmetric, in which case this is every piece.
+    pub fn canonical_opening_moves(&self) -> MoveSet {
+        let symmetries = self._setup_symmetries();
+        if symmetries.len() == 1 {
+            return MoveSet::all();
+        }
+
+        (0..NUM_PIECES)
+            .filter(|&p| symmetries.iter().all(|transform| self.piecemap.transformed(p, transform) >= p))
+            .collect()
+    }
+
+    /// Pairs each representative in `canonical_opening_moves` with the size of its symmetry class, i.e. the number of
+    /// opening moves it stands for; the sizes always sum to `NUM_PIECES`.
+    pub fn canonical_openings_with_multiplicity(&self) -> Vec<(usize, usize)> {
+        let symmetries = self._setup_symmetries();
+        let mut sizes = vec![0usize; NUM_PIECES];
+        for p in 0..NUM_PIECES {
+            let representative = symmetries.iter().map(|transform| self.piecemap.transformed(p, transform)).min().unwrap_or(p);
+            sizes[representative] += 1;
+        }
+
+        sizes.into_iter().enumerate().filter(|&(_, size)| size > 0).collect()
+    }
+
+    /// The board transforms (including the identity) that leave the setup symbols unchanged.
+    fn _setup_symmetries(&self) -> Vec<Transform> {
+        Transform::all().into_iter().filter(|transform| {
+            (0..BOARD_SIZE).all(|row| (0..BOARD_SIZE).all(|col| {
+                let coord = Coord::new(row, col);
+                self.get_unchecked(&coord).cell_value() == self.get_unchecked(&transform.apply_board(&coord)).cell_value()
+            }))
+        }).collect()
+    }
+
+    /// Counts the covered cells of each kind (in `Tile::all` order) orthogonally bordering the given cell.
+    pub fn adjacent_tile_counts(&self, coord: &Coord) -> [u8; 4] {
+        let counts = self.edge_mask.get_unchecked(coord);
+        Tile::all().map(|tile| counts.count(tile))
+    }
+
+    /// Determines whether covering the given (uncovered) cell would fill a 2x2 box in which the other three cells are
+    /// already covered, i.e. whether foursquare protects it.
+    pub fn completes_foursquare(&self, coord: &Coord) -> bool {
+        !self.cover.contains(coord) && self.foursquare_mask.three(coord)
+    }
+
     /// Determines the scoring symbol at a given row and column on the board, if any exists.
     pub fn cell(&self, coord: &Coord) -> Result<Option<Player>> {
         self.get(coord).map(|v: BoardCell| v.cell_value())
     }
 
+    /// Determines the set of pieces that can never be played again, either because they overlap a covered cell or
+    /// because their kind has run out in the bag.
+    pub fn dead_moves(&self) -> MoveSet {
+        let mut dead: MoveSet = (0..NUM_PIECES)
+            .filter(|&p| self.piecemap.coordset(p).intersects(&self.cover))
+            .collect();
+
+        for tile in Tile::all() {
+            if self.is_bag_exhausted(tile) {
+                dead.union_inplace(self.piecemap.pieces_of_type(tile));
+            }
+        }
+
+        dead
+    }
+
     /// Determines the "effective score" (i.e. the heuristic score) of the board.
     pub fn effective_score(&self) -> i16 {
         self._true_effective_score() * self.player_to_move.perspective()
@@ -119,9 +207,33 @@ impl<'a> Board<'a> {
         self.get(coord).map(|v: BoardCell| v.lits_value())
     }
 
-    /// Determines whether or not the state is terminal.
+    /// Determines whether the position could have arisen in a legal game; see `Board::validate_position`.
+    pub fn is_legal_position(&self) -> bool {
+        self.validate_position().is_ok()
+    }
+
+    /// Determines whether or not the state is terminal, either because no move remains or because the ply cap (if
+    /// any) has been reached.
     pub fn is_terminal(&self) -> bool {
-        !self._any_valid_move()
+        self.options.ply_cap.is_some_and(|cap| self.move_count() >= cap) || !self._any_valid_move()
+    }
+
+    /// Describes every legal move as a JSON array of `{id, kind, coords, notation}` objects, so that clients can render
+    /// placements without parsing notation. The swap, when legal, has a null kind and no coords.
+    pub fn legal_moves_json(&self) -> String {
+        let moves = self.valid_moves_set().iter().map(|id| {
+            let (kind, coords) = match id {
+                NULL_MOVE => (None, vec![]),
+                _         => (Some(format!("{:?}", self.piecemap.get_kind(id))), self.piecemap.coordset(id).iter().map(|c| c.notate()).collect()),
+            };
+            serde_json::json!({ "id": id, "kind": kind, "coords": coords, "notation": self.piecemap.notate(id) })
+        }).collect::<Vec<_>>();
+        serde_json::Value::Array(moves).to_string()
+    }
+
+    /// Determines the number of plies played, where the swap counts as a ply.
+    pub fn move_count(&self) -> usize {
+        self.history.len() + self.swapped as usize
     }
 
     /// Returns a new board. If a symbol map is provided, use it, otherwise generate one.
@@ -158,8 +270,10 @@ impl<'a> Board<'a> {
             neighbours: CoordSet::default(),
             unreachable: CoordSet::default(),
             protected: CoordSet::default(),
+            protected_uncovered: CoordSet::default(),
             symbols,
             piece_bag: [PIECES_PER_KIND; 4],
+            options: BoardOptions::default(),
             piecemap,
             player_to_move: Player::X,
             score: 0,
@@ -212,8 +326,17 @@ impl<'a> Board<'a> {
     /// Returns the full gamestring for this board. If a swap was played, the gamestring is mindful of this fact,
     /// and the starting positional fragment is a negation of the current visible board.
     pub fn notate(&self) -> String {
-        let mut fragments: Vec<String> = vec![];
-        fragments.push(self.cells.notate(self.swapped)); // 
+        self._notate_from(self.cells.notate(self.swapped))
+    }
+
+    /// Returns the full gamestring for this board, like `Board::notate`, but with the 20-character compressed setup.
+    pub fn notate_compressed(&self) -> String {
+        self._notate_from(crate::battle_of_lits::notation::_compress_grid(&self.cells, self.swapped))
+    }
+
+    /// Appends the move fragments to the given setup fragment.
+    fn _notate_from(&self, setup: String) -> String {
+        let mut fragments: Vec<String> = vec![setup];
 
         for (i, mv) in self.history.iter().enumerate() {
             fragments.push(self.piecemap.notate(*mv));
@@ -224,6 +347,68 @@ impl<'a> Board<'a> {
         fragments.join("; ")
     }
 
+    /// Classifies the game phase by the fraction of pieces played.
+    pub fn phase(&self) -> Phase {
+        let total = 4 * PIECES_PER_KIND;
+        Phase::from_played(total - self.pieces_remaining(), total)
+    }
+
+    /// Determines whether every piece of the given kind has been played.
+    pub fn is_bag_exhausted(&self, kind: Tile) -> bool {
+        self.piece_bag[kind as usize] == 0
+    }
+
+    /// Determines the number of pieces left in the bag, across all kinds.
+    pub fn pieces_remaining(&self) -> usize {
+        self.piece_bag.iter().sum()
+    }
+
+    /// Rebuilds this game's setup position with the same options, then plays the given plies onto it unchecked.
+    pub fn replay_from(&self, plies: &[usize]) -> Board<'a> {
+        let mut board = Board::new(Some(self.cells.setup(self.swapped)), self.piecemap).with_options(self.options);
+        for &mv in plies {
+            match mv {
+                NULL_MOVE => board.pass_unchecked_engine(),
+                _         => board.play_unchecked_engine(mv),
+            };
+        }
+        board
+    }
+
+    /// Loads a game from a gamestring, checking that each of its moves is legal in turn.
+    pub fn from_gamestring(gamestr: &GameString, piecemap: &'a PieceMap) -> Result<Board<'a>> {
+        let mut board = Board::new(Some(gamestr.setup.grid), piecemap);
+        for MoveString { repr, tetromino } in &gamestr.moves {
+            match tetromino {
+                Some(t) => board.play(piecemap.try_and_find(&t.real_coords())?),
+                None    => board.pass(),
+            }.with_context(|| format!("while playing {repr}"))?;
+        }
+        Ok(board)
+    }
+
+    /// Rebuilds this game under a board transform: the setup symbols move to their transformed cells and each ply is
+    /// replaced by the image of its piece, so every derived mask is recomputed by replaying the transformed game.
+    pub fn apply_transform(&self, transform: Transform) -> Board<'a> {
+        let setup = self.cells.setup(self.swapped);
+        let mut cells = setup;
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                let image = transform.apply_board(&Coord::new(row, col));
+                cells.0[image.row][image.col] = setup.0[row][col];
+            }
+        }
+
+        let mut board = Board::new(Some(cells), self.piecemap).with_options(self.options);
+        for mv in self.plies() {
+            match mv {
+                NULL_MOVE => board.pass_unchecked_engine(),
+                _         => board.play_unchecked_engine(self.piecemap.transformed(mv, &transform)),
+            };
+        }
+        board
+    }
+
     /// Implements the swap rule.
     /// 
     /// In this engine, we cannot change the colour assigned to a playing agent, so we must instead recontextualize the board to support the worldview of the swap.
@@ -245,38 +430,204 @@ impl<'a> Board<'a> {
         self.swap();
     }
 
+    /// Undoes the swap, which is only legal while it is the latest ply. Since the swap is its own inverse, this
+    /// re-negates the symbols and hands control back to O.
+    pub fn unswap(&mut self) -> Result<()> {
+        if self.swapped && self.history.len() == 1 {
+            self.swap();
+            Ok(())
+        } else {
+            Err(anyhow!("the swap can only be undone directly after it is played"))
+        }
+    }
+
     /// Plays a move on this board, if valid.
     pub fn play(&mut self, mv: usize) -> Result<()> {
         if self.valid_moves_set().contains(mv) {
             self.play_unchecked(&self.piecemap.get_piece(mv), mv);
             Ok(())
+        } else if mv < NUM_PIECES && self.is_bag_exhausted(self.piecemap.get_kind(mv)) {
+            Err(anyhow!("no {:?} tiles remaining for {}", self.piecemap.get_kind(mv), self.piecemap.notate(mv)))
         } else {
             Err(anyhow!("move {mv} is not valid in this position"))
         }
     }
 
+    /// Plays a move (or the swap) as in `Board::play`, reporting the score change and the cells it protected.
+    pub fn play_verbose(&mut self, mv: usize) -> Result<PlayOutcome> {
+        let score_before = self.score;
+        let protected_before = self.protected_uncovered;
+
+        match mv {
+            NULL_MOVE => self.pass()?,
+            _         => self.play(mv)?,
+        };
+
+        Ok(PlayOutcome {
+            id: mv,
+            score_before,
+            score_after: self.score,
+            newly_protected: self.protected_uncovered.difference(&protected_before),
+        })
+    }
+
     /// Plays a piece with no checks onto the board; engine only.
     pub fn play_unchecked_engine(&mut self, mv: usize) -> () {
         self.play_unchecked(&self.piecemap.get_piece(mv), mv);
     }
 
+    /// Plays each move in order (the null move is the swap), collecting the gamestring after each one.
+    /// 
+    /// Stops at the first illegal move, reporting its index in the sequence; moves before it stay played.
+    pub fn play_sequence(&mut self, moves: &[usize]) -> Result<Vec<String>> {
+        let mut notations = Vec::with_capacity(moves.len());
+        for (i, &mv) in moves.iter().enumerate() {
+            match mv {
+                NULL_MOVE => self.pass(),
+                _         => self.play(mv),
+            }.with_context(|| format!("move {i} ({}) in the sequence is illegal", self.piecemap.notate(mv)))?;
+            notations.push(self.notate());
+        }
+        Ok(notations)
+    }
+
+    /// Gets the plies played so far in order, with the swap (if any) as the null move.
+    pub fn plies(&self) -> Vec<usize> {
+        let mut plies = self.history.clone();
+        if self.swapped {
+            plies.insert(1, NULL_MOVE);
+        }
+        plies
+    }
+
+    /// Iterates over every position in this game, from the setup up to (and including) the current position.
+    pub fn positions_iter(&self) -> impl Iterator<Item = Board<'a>> + use<'a> {
+        let mut board = self.replay_from(&[]);
+        let first = board.clone();
+        std::iter::once(first).chain(self.plies().into_iter().map(move |mv| {
+            match mv {
+                NULL_MOVE => board.pass_unchecked_engine(),
+                _         => board.play_unchecked_engine(mv),
+            };
+            board.clone()
+        }))
+    }
+
     /// Determines the current player to move. X is the player when the number of played moves is even,
     /// since they start the game off at 0 moves on board.
     pub fn player_to_move(&self) -> Player {
         self.player_to_move
     }
 
+    /// Edits the scoring symbol at a cell, also setting its 180-degree counterpart to the opposite symbol so that the
+    /// setup stays rotationally symmetric. Only legal before any move has been played.
+    pub fn set_symbol(&mut self, coord: &Coord, player: Option<Player>) -> Result<()> {
+        if self.move_count() > 0 {
+            return Err(anyhow!("symbols can only be edited before any move is played"));
+        }
+        if !coord.in_bounds() {
+            return Err(anyhow!("invalid coordinate {}", OffsetCoord::from(coord)));
+        }
+
+        let mirror = Coord::new(BOARD_SIZE - 1 - coord.row, BOARD_SIZE - 1 - coord.col);
+        for (target, value) in [(*coord, player), (mirror, player.map(|p| -p))] {
+            self.set_cell_unchecked(&target, value);
+            match value {
+                Some(_) => self.symbols.insert(&target),
+                None    => self.symbols.remove(&target),
+            };
+        }
+        Ok(())
+    }
+
+    /// Determines the result of the game, scoring the position as it stands; `None` while the game is still going.
+    pub fn result(&self) -> Option<GameResult> {
+        self.is_terminal().then(|| GameResult::from_score(self.score))
+    }
+
     /// Gets the naive score on the board in X's perspective.
     pub fn score(&self) -> i16 {
         self.score
     }
 
+    /// Estimates how much the player to move gains by swapping rather than replying, from their own perspective.
+    /// 
+    /// The swap hands the swapper the other side of the material, so the gain is twice what the opening concedes to them.
+    /// We deliberately compare material rather than the effective score, because the heuristic terms depend on whose turn
+    /// it is and so don't compare like for like across the swap.
+    pub fn swap_value(&self) -> i16 {
+        -2 * self.score * self.player_to_move.perspective()
+    }
+
     /// Returns a set of valid moves in the current position. Does so using _m a g i c_, computing 99% of
     /// validity checks in constant time and saving n-piece foursquare detection for last.
     pub fn valid_moves(&self, moves: &mut Vec<usize>) {
         self._compute_valid_moves(moves);
     }
 
+    /// Checks the invariants of a legal position, namely that:
+    /// 1. the symbols are rotationally antisymmetric (each X is opposite an O), and
+    /// 2. no foursquare is fully covered by pieces, and
+    /// 3. no two pieces of the same kind touch along an edge.
+    /// 
+    /// Boards built through `Board::play` maintain these by construction, but imported setups may not.
+    pub fn validate_position(&self) -> Result<()> {
+        crate::battle_of_lits::notation::_validate_rotational_symmetry(&self.cells)
+            .context("setup is not rotationally symmetric")?;
+
+        for row in 0..(BOARD_SIZE - 1) {
+            for col in 0..(BOARD_SIZE - 1) {
+                if self.foursquare_mask.count(&Coord::new(row, col)) == 4 {
+                    return Err(anyhow!("foursquare at {} is fully covered", Coord::new(row, col)));
+                }
+            }
+        }
+        if let Some((lhs, rhs)) = self.same_kind_adjacencies().first() {
+            return Err(anyhow!("pieces at {lhs} and {rhs} are of the same kind and touch"));
+        }
+        Ok(())
+    }
+
+    /// Finds the pairs of orthogonally adjacent cells covered by different pieces of the same kind, each pair once
+    /// (with the upper or left cell first); this is always empty in a legally played game.
+    pub fn same_kind_adjacencies(&self) -> Vec<(Coord, Coord)> {
+        let mut owners = [[None; BOARD_SIZE]; BOARD_SIZE];
+        for &mv in self.history.iter().filter(|&&mv| mv != NULL_MOVE) {
+            for coord in self.piecemap.coordset(mv).iter() {
+                owners[coord.row][coord.col] = Some(mv);
+            }
+        }
+
+        let mut pairs = vec![];
+        for coord in self.cover.iter() {
+            let Some(kind) = self.get_unchecked(&coord).lits_value() else { continue };
+            if self.edge_mask.get_unchecked(&coord).count(kind) == 0 {
+                continue; // no tile of this kind borders the cell at all, not even from its own piece
+            }
+            for other in [Coord::new(coord.row + 1, coord.col), Coord::new(coord.row, coord.col + 1)] {
+                if other.row < BOARD_SIZE && other.col < BOARD_SIZE
+                    && self.get_unchecked(&other).lits_value() == Some(kind)
+                    && owners[other.row][other.col] != owners[coord.row][coord.col]
+                {
+                    pairs.push((coord, other));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Applies engine-side options and rule variants to this board.
+    pub fn with_options(mut self, options: BoardOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Plays this board under a variant that ends the game after the given number of plies.
+    pub fn with_ply_cap(mut self, cap: usize) -> Self {
+        self.options = self.options.with_ply_cap(cap);
+        self
+    }
+
     /// Gets a hash for the position. Since the searcher maintains an instance over
     /// multiple games, we need both the symbol zobrist and the move zobrist.
     /// Associativity of XOR makes it pretty easy to write; each bit of the output hash
@@ -285,3 +636,484 @@ impl<'a> Board<'a> {
         self.zobrist_hash
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::battle_of_lits::prelude::*;
+    use crate::battle_of_lits::testing::*;
+
+    #[test]
+    fn adjacent_tile_counts_track_bordering_pieces() {
+        let board = board(&format!("{SETUP}; L[65,75,85,86]; I[96,97,98,99]"));
+        assert_eq!(board.adjacent_tile_counts(&Coord::new(6, 6)), [1, 0, 0, 0]); // beside the L's top cell
+        assert_eq!(board.adjacent_tile_counts(&Coord::new(8, 7)), [1, 1, 0, 0]); // beside the L's foot, above the I
+        assert_eq!(board.adjacent_tile_counts(&Coord::new(0, 0)), [0; 4]);
+
+        let swapped = board.apply_transform(Transform::Rot180____);
+        assert_eq!(swapped.adjacent_tile_counts(&Coord::new(3, 3)), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn apply_transform_preserves_the_outcome() {
+        for gamestr in [format!("{SETUP}; {FINISHED}"), format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]")] {
+            let game = board(&gamestr);
+            for transform in Transform::all() {
+                let image = game.apply_transform(transform);
+                assert_eq!(image.score(), game.score(), "{transform:?}");
+                assert_eq!(image.is_terminal(), game.is_terminal(), "{transform:?}");
+                assert_eq!(image.move_count(), game.move_count());
+                assert!(image.is_legal_position());
+            }
+            assert_eq!(game.apply_transform(Transform::Identity__).notate(), game.notate());
+        }
+    }
+
+    #[test]
+    fn best_defensive_move_is_legal() {
+        let board = board(&format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]"));
+        let mv = board.best_defensive_move().unwrap();
+        assert!(board.valid_moves_set().contains(mv));
+
+        assert_eq!(self::board(&format!("{SETUP}; {FINISHED}")).best_defensive_move(), None);
+    }
+
+    #[test]
+    fn evaluation_saturates_instead_of_wrapping() {
+        assert_eq!(Board::_weighted_sum([i16::MAX, 1000, 1000, 0, 0, 0], i16::MAX), i16::MAX);
+        assert_eq!(Board::_weighted_sum([i16::MIN, -1000, 0, 1000, 0, 0], i16::MAX), -i16::MAX);
+        assert_eq!(Board::_weighted_sum([100, 2, 0, 0, 0, 0], 150), 150);
+
+        let clamped = board(&format!("{SETUP}; I[38,37,36,35]")).with_options(BoardOptions::new().with_eval_clamp(3));
+        assert!(clamped.effective_score().abs() <= 3);
+    }
+
+    #[test]
+    fn excluded_moves_are_left_out() {
+        let board = board(&format!("{SETUP}; I[38,37,36,35]"));
+        let mut exclude = MoveSet::default();
+        exclude.insert(piece("L[53,54,55,45]")).insert(NULL_MOVE).insert(piece("I[38,37,36,35]")); // the last is already illegal
+
+        let remaining = board.valid_moves_excluding(&exclude);
+        assert!(!remaining.contains(piece("L[53,54,55,45]")) && !remaining.contains(NULL_MOVE));
+        assert_eq!(remaining.len(), board.valid_moves_set().len() - 2);
+        assert_eq!(board.valid_moves_excluding(&MoveSet::default()).len(), board.valid_moves_set().len());
+    }
+
+    #[test]
+    fn legal_moves_touching_cover_or_border_the_cell() {
+        let board = board(&format!("{SETUP}; I[38,37,36,35]"));
+        let coord = Coord::new(2, 6);
+        let touching = board.legal_moves_touching(&coord);
+        assert!(!touching.is_empty());
+        for mv in touching.iter() {
+            assert!(board.valid_moves_set().contains(mv));
+            assert!(board.piecemap.coordset(mv).contains(&coord) || board.piecemap.neighbours(mv).contains(&coord));
+        }
+    }
+
+    #[test]
+    fn exact_endgame_replaces_the_incremental_unreachable_set() {
+        let heuristic = board(&format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]"));
+        assert_eq!(heuristic.pieces_remaining(), 18);
+
+        let exact = heuristic.clone().with_options(BoardOptions::new().with_exact_endgame(18));
+        // the incremental set writes off symbols that a future piece can still cover
+        let phantom = heuristic._unreachable_cells().difference(&exact._unreachable_cells()).intersect(&exact.symbols);
+        assert!(!phantom.is_empty());
+        assert!(phantom.iter().all(|coord| exact.is_cell_reachable(&coord)));
+        assert_ne!(exact.effective_score(), heuristic.effective_score());
+
+        let early = heuristic.clone().with_options(BoardOptions::new().with_exact_endgame(17));
+        assert_eq!(early.effective_score(), heuristic.effective_score());
+    }
+
+    #[test]
+    fn forced_cells_are_shared_by_every_move() {
+        let endgame = board(&format!("{SETUP}; I[24,34,44,54]; S[42,52,53,63]; T[03,04,05,14]; T[32,22,12,23]; S[45,46,36,37]; T[51,61,71,60]; L[02,01,00,10]; I[09,08,07,06]; L[72,82,92,91]; L[58,48,38,39]; T[79,78,77,68]; L[85,86,87,97]"));
+        assert_eq!(endgame.valid_moves_set().iter().collect::<BTreeSet<_>>(), BTreeSet::from([piece("T[74,84,94,83]"), piece("S[74,75,65,66]")]));
+        assert_eq!(endgame.forced_cells().to_sorted_vec(), vec![Coord::new(7, 4)]);
+
+        assert!(board(SETUP).forced_cells().is_empty());
+        assert!(board(&format!("{SETUP}; I[38,37,36,35]")).forced_cells().is_empty()); // the swap is legal and covers nothing
+    }
+
+    #[test]
+    fn score_volatility_summarizes_the_trajectory() {
+        let game = board(&format!("{SETUP}; {FINISHED}"));
+        // T[48,..] covers O, L[57,..] covers X, S[12,..] covers two Os, I[45,..] covers X, T[99,..] covers four Os and
+        // L[30,..] covers X; the other nine plies cover no symbols
+        assert_eq!(game.score_trajectory(), [0, 0, 0, 1, 0, 2, 1, 1, 1, 1, 5, 4, 4, 4, 4, 4]);
+
+        let (max_swing, last, mean) = game.score_volatility();
+        assert_eq!((max_swing, last), (4, 4));
+        assert!((mean - 10.0 / 15.0).abs() < 1e-6);
+        assert_eq!(board(SETUP).score_volatility(), (0, 0, 0.0));
+    }
+
+    #[test]
+    fn tempo_counts_free_frontier_symbols() {
+        let open = board(&format!("{SETUP}; I[01,02,03,04]")); // borders X at 00 and 11 and O at 12
+        assert_eq!(open.tempo_advantage(), 1);
+
+        let sealed = board(&format!("{SETUP}; I[01,02,03,04]; L[10,11,21,31]")); // 00 and 12 are protected, O at 22 is not
+        assert_eq!(sealed.tempo_advantage(), -1);
+        assert_eq!(board(SETUP).tempo_advantage(), 0);
+    }
+
+    #[test]
+    fn completing_cells_fill_a_three_covered_box() {
+        let pocket = board(&format!("{SETUP}; I[01,02,03,04]; L[10,11,21,31]"));
+        assert!(pocket.completes_foursquare(&Coord::new(0, 0)));
+        assert!(pocket.completes_foursquare(&Coord::new(2, 0)));
+        assert!(!pocket.completes_foursquare(&Coord::new(0, 1))); // already covered
+        assert!(!pocket.completes_foursquare(&Coord::new(5, 5)));
+
+        for coord in pocket.protected.iter() {
+            assert_eq!(pocket.completes_foursquare(&coord), !pocket.cover.contains(&coord));
+        }
+    }
+
+    #[test]
+    fn pretty_plain_is_ascii() {
+        let game = board(&format!("{SETUP}; I[38,37,36,35]"));
+        let plain = game.pretty_plain();
+        assert!(plain.is_ascii());
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain.lines().next(), Some("XXX......."));
+        assert_eq!(plain.lines().nth(3), Some(".....IIII."));
+    }
+
+    #[test]
+    fn moves_filling_match_the_pocket_exactly() {
+        let board = board(&format!("{SETUP}; I[38,37,36,35]"));
+        let pocket = ["53", "54", "55", "45"].map(|c| c.parse::<Coord>().unwrap());
+        let filling = board.moves_filling(&CoordSet::from_iter(pocket));
+        assert_eq!(filling.iter().collect::<Vec<_>>(), vec![piece("L[53,54,55,45]")]);
+
+        let too_small = CoordSet::from_iter(pocket[..3].iter().copied());
+        assert!(board.moves_filling(&too_small).is_empty());
+        let occupied = CoordSet::from_iter(["35", "36", "37", "38"].map(|c| c.parse::<Coord>().unwrap()));
+        assert!(board.moves_filling(&occupied).is_empty());
+    }
+
+    #[test]
+    fn play_verbose_reports_the_outcome() {
+        let mut board = board(&format!("{SETUP}; I[38,37,36,35]"));
+        let mv = piece("L[04,14,24,25]"); // covers the O at 24 and closes three foursquares
+        let outcome = board.play_verbose(mv).unwrap();
+
+        assert_eq!(outcome.id, mv);
+        assert_eq!(outcome.score_after, board.score());
+        assert_eq!(outcome.score_delta(), 1);
+        assert_eq!(outcome.newly_protected.to_sorted_vec(), vec![Coord::new(1, 5), Coord::new(2, 6), Coord::new(3, 4)]);
+        assert!(board.play_verbose(mv).is_err());
+    }
+
+    #[test]
+    fn plies_remaining_estimate_is_bounded_by_reachable_cells() {
+        let opening = board(SETUP);
+        assert_eq!(opening.plies_remaining_estimate(), opening.pieces_remaining());
+
+        let moves = FINISHED.split("; ").take(14).collect::<Vec<_>>().join("; ");
+        let closed = board(&format!("{SETUP}; {moves}"));
+        assert_eq!(closed.plies_remaining_estimate(), closed.reachable_cells().len() / 4);
+        assert!(closed.plies_remaining_estimate() < closed.pieces_remaining());
+    }
+
+    #[test]
+    fn playing_from_an_empty_bag_says_so() {
+        let mut board = board(BLANK);
+        for notation in ["L[00,10,20,21]", "L[03,13,23,24]", "L[06,16,26,27]", "L[50,60,70,71]", "L[53,63,73,74]"] {
+            board.play_unchecked_engine(piece(notation)); // five disjoint Ls, ignoring adjacency
+        }
+        assert!(board.is_bag_exhausted(Tile::L));
+        assert!(!board.is_bag_exhausted(Tile::I));
+
+        let err = board.play(piece("L[56,66,76,77]")).unwrap_err();
+        assert!(err.to_string().contains("no L tiles remaining"), "{err}");
+        let err = board.play(piece("I[00,01,02,03]")).unwrap_err();
+        assert!(!err.to_string().contains("remaining"), "{err}");
+    }
+
+    #[test]
+    fn protected_uncovered_stays_in_sync() {
+        let game = board(&format!("{SETUP}; {FINISHED}"));
+        for position in game.positions_iter() {
+            assert_eq!(position.protected_uncovered.to_u128(), position.protected.difference(&position.cover).to_u128());
+        }
+    }
+
+    #[test]
+    fn same_kind_adjacencies_are_reported() {
+        let legal = board(&format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]; L[14,15,16,26]"));
+        assert!(legal.same_kind_adjacencies().is_empty());
+
+        let mut imported = board(&format!("{SETUP}; I[38,37,36,35]"));
+        imported.play_unchecked_engine(piece("I[48,47,46,45]")); // bypasses the check that would reject it
+        assert_eq!(imported.same_kind_adjacencies().len(), 4);
+        assert!(imported.same_kind_adjacencies().contains(&(Coord::new(3, 5), Coord::new(4, 5))));
+        assert!(imported.validate_position().is_err());
+    }
+
+    #[test]
+    fn dead_moves_are_never_valid() {
+        let board = board(&format!("{SETUP}; I[96,97,98,99]; L[65,75,85,86]"));
+        let dead = board.dead_moves();
+
+        assert!(dead.contains(piece("I[96,97,98,99]")));
+        assert!(dead.contains(piece("L[65,75,85,86]")));
+        assert!(dead.intersect(&board.valid_moves_set()).len() == 0);
+    }
+
+    #[test]
+    fn canonical_openings_cover_every_orbit() {
+        assert_eq!(board(SETUP).canonical_opening_moves().len(), NUM_PIECES);
+
+        for (setup, symmetries) in [(BLANK, Transform::all().to_vec()), (TRANSPOSED, vec![Transform::ReflRot90_])] {
+            let canonical = board(setup).canonical_opening_moves();
+            assert!(canonical.len() < NUM_PIECES / 2 + 50);
+            for p in 0..NUM_PIECES {
+                let orbit = symmetries.iter().map(|t| piecemap().transformed(p, t)).chain([p]);
+                assert!(orbit.into_iter().any(|q| canonical.contains(q)), "{} has no representative", piecemap().notate(p));
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_opening_classes_partition_the_pieces() {
+        for setup in [SETUP, BLANK, TRANSPOSED] {
+            let board = board(setup);
+            let classes = board.canonical_openings_with_multiplicity();
+            assert_eq!(classes.iter().map(|&(_, size)| size).sum::<usize>(), NUM_PIECES);
+            assert!(classes.iter().map(|&(p, _)| p).eq(board.canonical_opening_moves().iter()));
+        }
+    }
+
+    #[test]
+    fn compact_bytes_roundtrip_and_are_small() {
+        for gamestr in [format!("{SETUP}; {FINISHED}"), format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]"), SETUP.to_string()] {
+            let original = board(&gamestr);
+            let bytes = original.to_compact_bytes();
+            let recovered = Board::from_compact_bytes(&bytes, piecemap()).unwrap();
+            assert_eq!(recovered.notate(), original.notate());
+            assert_eq!(recovered.zobrist(), original.zobrist());
+            assert!(4 * bytes.len() < original.notate().len(), "{} bytes against {}", bytes.len(), original.notate().len());
+        }
+
+        let bytes = board(&format!("{SETUP}; {FINISHED}")).to_compact_bytes();
+        assert!(Board::from_compact_bytes(&bytes[..bytes.len() - 1], piecemap()).is_err());
+        assert!(Board::from_compact_bytes(&bytes[..10], piecemap()).is_err());
+    }
+
+    #[test]
+    fn compact_bytes_reject_malformed_ids() {
+        let setup = board(SETUP).to_compact_bytes();
+        let decode = |tail: &[u8]| Board::from_compact_bytes(&[setup.as_slice(), tail].concat(), piecemap());
+
+        assert!(decode(&[0xff, 0x7f]).is_err()); // 16383, past the null move
+        assert!(decode(&[0x8d, 0x0a]).is_err()); // 1293, one past the null move
+        assert!(decode(&[0x80, 0x80, 0x00]).is_err()); // a continuation bit on the second byte
+        assert!(decode(&[0x8c, 0x0a]).is_err()); // the null move, but the swap isn't legal before the first ply
+        assert_eq!(decode(&[]).unwrap().notate(), SETUP);
+    }
+
+    #[test]
+    fn compressed_notation_reparses() {
+        for gamestr in [format!("{SETUP}; I[96,97,98,99]; L[65,75,85,86]"), format!("{SETUP}; I[96,97,98,99]; swap")] {
+            let original = board(&gamestr);
+            let compressed = original.notate_compressed();
+            assert!(compressed.len() < gamestr.len());
+
+            let recovered = board(&compressed);
+            assert_eq!(recovered.notate(), original.notate());
+            assert_eq!(recovered.zobrist(), original.zobrist());
+        }
+    }
+
+    #[test]
+    fn unswap_restores_the_position() {
+        let before = board(&format!("{SETUP}; I[96,97,98,99]"));
+        let mut swapped = before.clone();
+        swapped.pass().unwrap();
+        assert_ne!(swapped.zobrist(), before.zobrist());
+
+        swapped.unswap().unwrap();
+        assert_eq!(swapped.notate(), before.notate());
+        assert_eq!(swapped.zobrist(), before.zobrist());
+        assert_eq!(swapped.player_to_move(), before.player_to_move());
+        assert!(swapped.unswap().is_err());
+
+        assert!(board(&format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]")).unswap().is_err());
+    }
+
+    #[test]
+    fn would_isolate_detects_sealed_pockets() {
+        let open = board(&format!("{SETUP}; I[01,02,03,04]"));
+        assert!(open.would_isolate(piece("L[10,11,21,31]"))); // seals the X at 00 into a one-cell pocket
+        assert!(!open.would_isolate(piece("L[05,15,25,26]")));
+    }
+
+    #[test]
+    fn is_cell_reachable_agrees_with_reachable_cells() {
+        let pocket = board(&format!("{SETUP}; I[01,02,03,04]; L[10,11,21,31]"));
+        assert!(!pocket.is_cell_reachable(&Coord::new(0, 0))); // sealed off
+        assert!(!pocket.is_cell_reachable(&Coord::new(0, 1))); // covered
+        assert!(pocket.is_cell_reachable(&Coord::new(5, 5)));
+
+        let reachable = pocket.reachable_cells();
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                let coord = Coord::new(row, col);
+                assert_eq!(pocket.is_cell_reachable(&coord), reachable.contains(&coord), "{coord}");
+            }
+        }
+    }
+
+    #[test]
+    fn legal_moves_json_lists_every_move() {
+        for gamestr in [format!("{SETUP}; I[96,97,98,99]"), format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]")] {
+            let board = board(&gamestr);
+            let json: serde_json::Value = serde_json::from_str(&board.legal_moves_json()).unwrap();
+            let moves = json.as_array().unwrap();
+            assert_eq!(moves.len(), board.valid_moves_set().len());
+
+            for mv in moves {
+                let id = mv["id"].as_u64().unwrap() as usize;
+                assert_eq!(mv["notation"], board.piecemap.notate(id));
+                if id != NULL_MOVE {
+                    assert_eq!(mv["coords"].as_array().unwrap().len(), 4);
+                    assert_eq!(piece(mv["notation"].as_str().unwrap()), id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn phase_follows_pieces_played() {
+        let fresh = board(SETUP);
+        assert_eq!(fresh.pieces_remaining(), 20);
+        assert_eq!(fresh.phase(), Phase::Opening);
+
+        let finished = board(&format!("{SETUP}; {FINISHED}"));
+        assert_eq!(finished.pieces_remaining(), 5);
+        assert_eq!(finished.phase(), Phase::Endgame);
+    }
+
+    #[test]
+    fn play_sequence_notates_each_move() {
+        let mut sequenced = board(SETUP);
+        let notations = sequenced.play_sequence(&[piece("I[96,97,98,99]"), NULL_MOVE]).unwrap();
+        assert_eq!(notations, [format!("{SETUP}; I[99,98,97,96]"), format!("{SETUP}; I[99,98,97,96]; swap")]);
+        assert_eq!(notations.last(), Some(&sequenced.notate()));
+
+        let err = sequenced.play_sequence(&[piece("L[65,75,85,86]"), piece("L[65,75,85,86]")]).unwrap_err();
+        assert!(err.to_string().starts_with("move 1 (L[65,75,85,86])"));
+    }
+
+    #[test]
+    fn positions_iter_replays_the_game() {
+        for gamestr in [format!("{SETUP}; {FINISHED}"), format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]")] {
+            let game = board(&gamestr);
+            let positions = game.positions_iter().collect::<Vec<_>>();
+            assert_eq!(positions.len(), game.move_count() + 1);
+            assert_eq!(positions[0].notate(), SETUP);
+
+            let last = positions.last().unwrap();
+            assert_eq!(last.notate(), game.notate());
+            assert_eq!(last.zobrist(), game.zobrist());
+        }
+    }
+
+    #[test]
+    fn ply_cap_ends_the_game_early() {
+        let uncapped = board(&format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]"));
+        assert!(!uncapped.is_terminal());
+        assert_eq!(uncapped.result(), None);
+
+        let capped = uncapped.clone().with_ply_cap(3);
+        assert!(!capped.valid_moves_set().is_empty());
+        assert!(capped.is_terminal());
+        assert_eq!(capped.result(), Some(GameResult::from_score(capped.score())));
+        assert!(!uncapped.clone().with_ply_cap(4).is_terminal());
+
+        let finished = board(&format!("{SETUP}; {FINISHED}"));
+        assert_eq!(finished.result(), Some(GameResult::Win(Player::X, 4)));
+    }
+
+    #[test]
+    fn quiescent_score_stands_pat_on_quiet_positions() {
+        let quiet = board(&format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]; S[52,62,61,71]"));
+        let mut noisy = vec![];
+        quiet.noisy_moves(&mut noisy);
+        assert!(noisy.is_empty());
+        assert_eq!(quiet.quiescent_score(4), quiet.effective_score());
+
+        let loud = board(&format!("{SETUP}; I[96,97,98,99]"));
+        assert_eq!(loud.quiescent_score(0), loud.effective_score());
+        assert!(loud.quiescent_score(2) >= loud.effective_score());
+    }
+
+    #[test]
+    fn rejects_asymmetric_setups() {
+        assert!(board(SETUP).is_legal_position());
+
+        let mut grid = board(SETUP).cells;
+        grid.0[0][5] = grid.0[0][5].with_cell(Some(Player::X)); // no matching O at 94
+        let err = Board::new(Some(grid), piecemap()).validate_position().unwrap_err();
+        assert!(format!("{err:#}").contains("do not match"));
+    }
+
+    #[test]
+    fn score_potential_counts_reachable_symbols() {
+        let open = board(&format!("{SETUP}; I[96,97,98,99]")); // covers three Os, everything else is still reachable
+        assert_eq!(open.score_potential(Player::X), 11);
+        assert_eq!(open.score_potential(Player::O), 8);
+
+        let pocket = board(&format!("{SETUP}; I[01,02,03,04]; L[10,11,21,31]")); // X at 00 is sealed off, O at 12 is protected
+        assert!(!pocket.reachable_cells().contains(&Coord::new(0, 0)));
+        assert!(!pocket.reachable_cells().contains(&Coord::new(1, 2)));
+        assert_eq!(pocket.score_potential(Player::X), 7);
+        assert_eq!(pocket.score_potential(Player::O), 10);
+    }
+
+    #[test]
+    fn influence_decays_away_from_a_symbol() {
+        let mut grid = board(BLANK).cells;
+        grid.0[4][4] = grid.0[4][4].with_cell(Some(Player::X));
+        let influence = Board::new(Some(grid), piecemap()).influence_map();
+
+        assert_eq!(influence[4][4], 4);
+        assert_eq!([influence[4][5], influence[2][4], influence[5][6], influence[4][8]], [3, 2, 1, 0]);
+        assert!(influence.iter().flatten().all(|&value| value >= 0));
+
+        let covered = board(&format!("{SETUP}; I[96,97,98,99]")).influence_map(); // the Os at 97, 98 and 99 are covered
+        let uncovered = board(SETUP).influence_map();
+        assert!(covered[9][9] > uncovered[9][9]);
+    }
+
+    #[test]
+    fn set_symbol_keeps_the_setup_symmetric() {
+        let mut editor = board(BLANK);
+        editor.set_symbol(&Coord::new(0, 0), Some(Player::X)).unwrap();
+        editor.set_symbol(&Coord::new(2, 7), Some(Player::O)).unwrap();
+        editor.set_symbol(&Coord::new(9, 9), Some(Player::X)).unwrap(); // overwrites the O mirrored from 00
+        editor.set_symbol(&Coord::new(7, 2), None).unwrap(); // clears the X mirrored from 27
+
+        assert!(editor.is_legal_position());
+        assert_eq!(editor.score(), 0);
+        assert_eq!(editor.zobrist(), board(&editor.notate()).zobrist());
+        assert_eq!(editor.notate(), format!("O{}X", ".".repeat(98)));
+
+        editor.play(piece("I[96,97,98,99]")).unwrap();
+        assert!(editor.set_symbol(&Coord::new(5, 5), Some(Player::X)).is_err());
+    }
+
+    #[test]
+    fn swap_value_follows_the_opening_material() {
+        let strong = board(&format!("{SETUP}; I[96,97,98,99]")); // X covers three Os
+        assert!(strong.swap_value() > 0);
+
+        let weak = board(&format!("{SETUP}; L[00,01,02,10]")); // X covers three of its own
+        assert!(weak.swap_value() < 0);
+    }
+}
@@ -26,6 +26,7 @@ impl<'a> Board<'a> {
 
             // Update cached protected cells for movegen and evaluator
             self.protected = self.foursquare_mask.protected_cells();
+            self.protected_uncovered = self.protected.difference(&self.cover);
         }
 
         { // meta information
@@ -93,11 +94,9 @@ impl<'a> Board<'a> {
             }
         }
 
-        let protected_uncovered = self.protected.difference(&self.cover);
-
         valid_moves.iter().any(|candidate| {
             // we also drop pieces that violate foursquare using protected cell check
-            !foursquare::violates(self.piecemap.coordset(candidate), &protected_uncovered)
+            !foursquare::violates(self.piecemap.coordset(candidate), &self.protected_uncovered)
         })
     }
 
@@ -133,9 +132,6 @@ impl<'a> Board<'a> {
 
         valid_moves.difference_inplace(&history); // remove played moves
 
-        // Compute protected cells once for all candidate moves
-        let protected_uncovered = self.protected.difference(&self.cover);
-
         valid_moves
             .iter().filter(|&p| {
                 // we drop pieces not in the bag.
@@ -144,12 +140,66 @@ impl<'a> Board<'a> {
                     return false;
                 }
                 // we also drop pieces that violate foursquare using protected cell check
-                !foursquare::violates(self.piecemap.coordset(p), &protected_uncovered)
+                !foursquare::violates(self.piecemap.coordset(p), &self.protected_uncovered)
             }).collect()
     }
 
+    /// Gets the legal moves that aren't in the given set, e.g. to steer a new game away from openings already tried.
+    pub fn valid_moves_excluding(&self, exclude: &MoveSet) -> MoveSet {
+        self.valid_moves_set().difference(exclude)
+    }
+
+    /// Gets the legal moves that cover or border the given cell, i.e. the placements involving a clicked cell.
+    pub fn legal_moves_touching(&self, coord: &Coord) -> MoveSet {
+        self.valid_moves_set().intersect(self.piecemap.pieces_touching(coord))
+    }
+
+    /// Gets the legal moves that exactly fill the given region. A piece is determined by its cells, so this is at most
+    /// one move, and always empty unless the region is a 4-cell tetromino shape.
+    pub fn moves_filling(&self, region: &CoordSet) -> MoveSet {
+        let mut moves = MoveSet::default();
+        let cells = region.to_sorted_vec();
+        let Ok(coords) = <[Coord; 4]>::try_from(cells) else {
+            return moves;
+        };
+
+        if let Ok(id) = self.piecemap.try_and_find(&coords.map(OffsetCoord::from))
+            && self.valid_moves_set().contains(id)
+        {
+            moves.insert(id);
+        }
+        moves
+    }
+
+    /// Gets the cells that every legal move covers, which is empty unless the moves all overlap. The swap covers
+    /// nothing, so this is also empty whenever it is legal, as it is when there are no legal moves at all.
+    pub fn forced_cells(&self) -> CoordSet {
+        let legal = self.valid_moves_set();
+        let mut moves = legal.iter();
+        let Some(first) = moves.next() else {
+            return CoordSet::default();
+        };
+
+        moves.try_fold(self._cells_of(first), |forced, mv| {
+            let forced = forced.intersect(&self._cells_of(mv));
+            (!forced.is_empty()).then_some(forced)
+        }).unwrap_or_default()
+    }
+
+    /// The cells a move covers, where the swap covers none.
+    fn _cells_of(&self, mv: usize) -> CoordSet {
+        match mv {
+            NULL_MOVE => CoordSet::default(),
+            _         => *self.piecemap.coordset(mv),
+        }
+    }
+
     pub fn _compute_valid_moves(&self, moves: &mut Vec<usize>) {
         match self.history.len() {
+            0 if self.options.canonical_openings => {
+                moves.extend(self.canonical_opening_moves().iter());
+                return;
+            },
             0 => { 
                 moves.reserve(1292);
                 moves.extend(0..NUM_PIECES);
@@ -191,14 +241,12 @@ impl<'a> Board<'a> {
             }
         }
 
-        let protected_uncovered = self.protected.difference(&self.cover);
-
         // Reserve capacity to avoid reallocations during collect_into
         moves.reserve(valid_moves.len());
 
         valid_moves
             .iter().filter(|&candidate| {
-                !foursquare::violates(self.piecemap.coordset(candidate), &protected_uncovered)
+                !foursquare::violates(self.piecemap.coordset(candidate), &self.protected_uncovered)
             }).collect_into(moves);
     }
 
@@ -244,8 +292,6 @@ impl<'a> Board<'a> {
             }
         }
 
-        let protected_uncovered = self.protected.difference(&self.cover);
-
         // Reserve capacity to avoid reallocations during collect_into
         moves.reserve(valid_moves.len());
 
@@ -256,7 +302,7 @@ impl<'a> Board<'a> {
                 }
 
                 // we also drop pieces that violate foursquare using protected cell check
-                !foursquare::violates(self.piecemap.coordset(p), &protected_uncovered)
+                !foursquare::violates(self.piecemap.coordset(p), &self.protected_uncovered)
             }).collect_into(moves);
     }
 }
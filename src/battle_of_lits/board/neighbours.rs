@@ -47,6 +47,11 @@ pub struct EdgeCounter {
 }
 
 impl EdgeCounter {
+    /// Gets the tile counts bordering the given cell; engine use only.
+    pub fn get_unchecked(&self, coord: &Coord) -> LITSEdgeCount {
+        self.counters[coord.row][coord.col]
+    }
+
     /// Updates the tile counts on the neighbours of a given tile unchecked; engine use only.
     pub fn update_unchecked(
         &mut self,
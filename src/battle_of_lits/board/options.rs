@@ -0,0 +1,55 @@
+/// Engine-side behaviours for a board, and the rule variants it is played under.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoardOptions {
+    /// Restricts movegen on the empty board to one representative per symmetry class of the setup.
+    pub canonical_openings: bool,
+
+    /// Ends the game once this many plies (counting the swap) have been played; `None` plays until no move remains.
+    pub ply_cap: Option<usize>,
+
+    /// Keys the transposition table by `Board::position_hash` instead of the incremental zobrist.
+    pub position_keyed_table: bool,
+
+    /// Bounds the magnitude of the heuristic evaluation; `None` only keeps it within the `i16` range.
+    pub eval_clamp: Option<i16>,
+
+    /// Once at most this many pieces remain, the evaluator uses exact reachability instead of the incremental set.
+    pub exact_endgame: Option<usize>,
+}
+
+impl BoardOptions {
+    /// Gets the default (rules-only) options.
+    pub fn new() -> BoardOptions {
+        BoardOptions::default()
+    }
+
+    /// Enables symmetry reduction of the opening move.
+    pub fn with_canonical_openings(mut self) -> BoardOptions {
+        self.canonical_openings = true;
+        self
+    }
+
+    /// Clamps heuristic evaluations into `[-bound, bound]`.
+    pub fn with_eval_clamp(mut self, bound: i16) -> BoardOptions {
+        self.eval_clamp = Some(bound);
+        self
+    }
+
+    /// Evaluates with exact reachability once at most `threshold` pieces remain in the bag.
+    pub fn with_exact_endgame(mut self, threshold: usize) -> BoardOptions {
+        self.exact_endgame = Some(threshold);
+        self
+    }
+
+    /// Keys the transposition table by position rather than by the moves played.
+    pub fn with_position_keyed_table(mut self) -> BoardOptions {
+        self.position_keyed_table = true;
+        self
+    }
+
+    /// Caps the game at the given number of plies.
+    pub fn with_ply_cap(mut self, cap: usize) -> BoardOptions {
+        self.ply_cap = Some(cap);
+        self
+    }
+}
@@ -0,0 +1,24 @@
+use crate::battle_of_lits::prelude::*;
+
+/// What a single move changed, as reported by `Board::play_verbose`.
+#[derive(Clone, Copy, Debug)]
+pub struct PlayOutcome {
+    /// The move that was played; `NULL_MOVE` for the swap.
+    pub id: usize,
+
+    /// The score from X's perspective before the move.
+    pub score_before: i16,
+
+    /// The score from X's perspective after the move.
+    pub score_after: i16,
+
+    /// The uncovered cells the move protected by foursquare, i.e. cells no piece can cover from now on.
+    pub newly_protected: CoordSet,
+}
+
+impl PlayOutcome {
+    /// The change in score from X's perspective.
+    pub fn score_delta(&self) -> i16 {
+        self.score_after - self.score_before
+    }
+}
@@ -0,0 +1,29 @@
+/// A coarse classification of how far a game has progressed, by the fraction of pieces played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Opening,
+    Midgame,
+    Endgame,
+}
+
+impl Phase {
+    /// Classifies a game in which `played` of `total` pieces have been placed; each phase covers a third of the bag.
+    pub fn from_played(played: usize, total: usize) -> Phase {
+        if 3 * played < total {
+            Phase::Opening
+        } else if 3 * played < 2 * total {
+            Phase::Midgame
+        } else {
+            Phase::Endgame
+        }
+    }
+
+    /// Notates the phase.
+    pub fn notate(&self) -> String {
+        match self {
+            Phase::Opening => "opening",
+            Phase::Midgame => "midgame",
+            Phase::Endgame => "endgame",
+        }.into()
+    }
+}
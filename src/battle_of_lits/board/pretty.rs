@@ -10,4 +10,18 @@ impl<'a> Board<'a> {
             }).join("")
         }).collect::<Vec<String>>().join("\n")
     }
+
+    /// Pretty-prints the board in plain ASCII: `.` for an empty cell, `X` or `O` for a symbol and `L`, `I`, `T` or `S`
+    /// for a tile, for terminals that can't render the emoji in `pretty`.
+    pub fn pretty_plain(&self) -> String {
+        self.cells.0.iter().map(|row| {
+            row.map(|cell| {
+                match (cell.lits_value(), cell.cell_value()) {
+                    (Some(tile), _)   => format!("{tile:?}"),
+                    (_, Some(player)) => player.notate(),
+                    (None, None)      => ".".into(),
+                }
+            }).join("")
+        }).collect::<Vec<String>>().join("\n")
+    }
 }
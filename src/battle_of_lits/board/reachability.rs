@@ -3,6 +3,96 @@ use super::*;
 const UNREACHABILITY_LOWER_BOUND: usize = 2;
 
 impl<'a> Board<'a> {
+    /// Determines the uncovered cells that a future piece could still cover.
+    /// 
+    /// Unlike the incrementally-maintained unreachable set, this is computed exactly (up to foursquare interactions
+    /// between future pieces) by:
+    /// 1. flooding outwards from the played network through cells that are neither covered nor protected
+    /// 2. keeping only the cells of pieces whose kind is still in the bag and that fit entirely inside that region
+    pub fn reachable_cells(&self) -> CoordSet {
+        let closed = self.cover.union(&self.protected);
+
+        let region = if self.history.is_empty() {
+            let mut open = closed;
+            open.neg_inplace();
+            open
+        } else {
+            CoordSet::reachable_closure(&self.neighbours, &closed)
+        };
+
+        let mut outside = region;
+        outside.neg_inplace();
+
+        let mut reachable = CoordSet::default();
+        for tile in Tile::all() {
+            if self.piece_bag[tile as usize] == 0 {
+                continue;
+            }
+            for piece in self.piecemap.pieces_of_type(tile).iter() {
+                let coords = self.piecemap.coordset(piece);
+                if !coords.intersects(&outside) {
+                    reachable.union_inplace(coords);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Estimates how many more pieces the game has room for: each needs four reachable cells, and there can be no more
+    /// than are left in the bag. This is tighter than the bag alone once the board closes up.
+    pub fn plies_remaining_estimate(&self) -> usize {
+        (self.reachable_cells().len() / 4).min(self.pieces_remaining())
+    }
+
+    /// Determines whether a future piece could still cover the given cell; this agrees with `Board::reachable_cells`,
+    /// but floods outwards from the played network only until it arrives at the target.
+    pub fn is_cell_reachable(&self, coord: &Coord) -> bool {
+        let mut open = self.cover.union(&self.protected);
+        open.neg_inplace();
+
+        if !open.contains(coord) {
+            return false;
+        }
+
+        // the cell must be coverable by some available piece that avoids blocked cells
+        let fits = Tile::all().into_iter().filter(|&tile| self.piece_bag[tile as usize] > 0).any(|tile| {
+            self.piecemap.pieces_of_type(tile).iter().any(|piece| {
+                let coords = self.piecemap.coordset(piece);
+                coords.contains(coord) && coords.difference_count(&open) == 0
+            })
+        });
+        if !fits || self.history.is_empty() {
+            return fits;
+        }
+
+        let mut visited = CoordSet::default();
+        let mut stack = self.neighbours.intersect(&open).to_sorted_vec();
+        while let Some(next) = stack.pop() {
+            if next == *coord {
+                return true;
+            }
+            if visited.contains(&next) {
+                continue;
+            }
+            visited.insert(&next);
+            stack.extend(self.piecemap.coord_neighbours(&next).intersect(&open).difference(&visited).iter());
+        }
+        false
+    }
+
+    /// Determines exactly whether playing the given (legal) move would leave some scoring cell, which a future piece
+    /// could cover now, impossible to cover afterwards; the cells the move itself covers don't count.
+    /// 
+    /// Unlike the piecemap's isolation data, this floods the board before and after the move, so it is slow but exact.
+    pub fn would_isolate(&self, mv: usize) -> bool {
+        let mut after = self.clone();
+        after.play_unchecked_engine(mv);
+
+        let mut lost = self.reachable_cells().intersect(&self.symbols);
+        lost.difference_inplace(self.piecemap.coordset(mv));
+        lost.difference_count(&after.reachable_cells()) > 0
+    }
+
     /// Updates the unreachable cells set after a piece has been placed.
     /// 
     /// This method detects cells that have become mathematically impossible to reach
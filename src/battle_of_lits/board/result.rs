@@ -0,0 +1,51 @@
+use crate::battle_of_lits::prelude::*;
+
+/// The outcome of a finished game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    /// The given player won by the given (positive) number of points.
+    Win(Player, i16),
+    Draw,
+}
+
+impl GameResult {
+    /// Determines the result from a final score in X's perspective.
+    pub fn from_score(score: i16) -> GameResult {
+        match score.signum() {
+             1 => GameResult::Win(Player::X, score),
+            -1 => GameResult::Win(Player::O, -score),
+             _ => GameResult::Draw,
+        }
+    }
+
+    /// The absolute score difference; 0 for a draw.
+    pub fn margin(&self) -> i16 {
+        match self {
+            GameResult::Win(_, margin) => *margin,
+            GameResult::Draw           => 0,
+        }
+    }
+
+    /// The winning player, if any.
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            GameResult::Win(player, _) => Some(*player),
+            GameResult::Draw           => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accessors() {
+        let cases = [(4, Some(Player::X), 4), (-3, Some(Player::O), 3), (0, None, 0)];
+        for (score, winner, margin) in cases {
+            let result = GameResult::from_score(score);
+            assert_eq!(result.winner(), winner);
+            assert_eq!(result.margin(), margin);
+        }
+    }
+}
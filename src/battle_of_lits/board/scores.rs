@@ -1,7 +1,142 @@
 
 use super::*;
 
+/// The taxicab distance beyond which an uncovered symbol no longer influences a cell.
+const INFLUENCE_RADIUS: usize = 3;
+
+/// The weights on material, unreachable symbols, security, threat, connectivity and constraint in the heuristic.
+const EVAL_WEIGHTS: [i16; 6] = [1, 50, 25, -15, 10, -5];
+
 impl<'a> Board<'a> {
+    /// The number of scoring cells belonging to the given player that some future piece could still cover.
+    /// 
+    /// Since this uses exact reachability, it is a cleaner (if slower) measure than the heuristic's unreachable term.
+    pub fn score_potential(&self, player: Player) -> i16 {
+        self.reachable_cells()
+            .intersect(&self.symbols)
+            .iter()
+            .filter(|coord| self.get_unchecked(coord).cell_value() == Some(player))
+            .count() as i16
+    }
+
+    /// A per-cell heatmap of nearby material from X's perspective: each uncovered symbol contributes to every cell within
+    /// `INFLUENCE_RADIUS` (by taxicab distance), with a weight that falls off linearly from `INFLUENCE_RADIUS + 1` on its
+    /// own cell. Positive values are cells X dominates, negative values are cells O dominates.
+    pub fn influence_map(&self) -> [[i16; BOARD_SIZE]; BOARD_SIZE] {
+        let mut influence = [[0i16; BOARD_SIZE]; BOARD_SIZE];
+        for symbol in self.symbols.difference(&self.cover).iter() {
+            let value = self.get_unchecked(&symbol).cell_value().unwrap().perspective();
+            let origin = OffsetCoord::from(symbol);
+            for (row, cells) in influence.iter_mut().enumerate() {
+                for (col, cell) in cells.iter_mut().enumerate() {
+                    let distance = origin.manhattan(OffsetCoord::from(Coord::new(row, col)));
+                    if distance <= INFLUENCE_RADIUS {
+                        *cell += value * (INFLUENCE_RADIUS + 1 - distance) as i16;
+                    }
+                }
+            }
+        }
+        influence
+    }
+
+    /// Which side has more free development, from X's perspective: the number of X symbols on the frontier (uncovered
+    /// cells bordering a played piece) that foursquare doesn't protect, less the number of such O symbols.
+    /// 
+    /// This uses the same frontier as the heuristic's threat and connectivity terms, but isn't folded into it.
+    pub fn tempo_advantage(&self) -> i16 {
+        self.neighbours
+            .intersect(&self.symbols)
+            .difference(&self.protected_uncovered)
+            .iter()
+            .map(|coord| self.get_unchecked(&coord).cell_value().unwrap().perspective())
+            .sum()
+    }
+
+    /// The material score (from X's perspective) after each ply of the game, starting from the setup.
+    pub fn score_trajectory(&self) -> Vec<i16> {
+        self.positions_iter().map(|position| position.score()).collect()
+    }
+
+    /// Summarizes how sharply the score moved over the game, as the largest change between consecutive plies, the final
+    /// score, and the mean absolute change per ply (which is 0 for a game with no plies).
+    pub fn score_volatility(&self) -> (i16, i16, f32) {
+        let trajectory = self.score_trajectory();
+        let deltas = trajectory.windows(2).map(|pair| (pair[1] - pair[0]).abs()).collect::<Vec<_>>();
+
+        let max_swing = deltas.iter().copied().max().unwrap_or(0);
+        let mean = match deltas.len() {
+            0 => 0.0,
+            n => deltas.iter().map(|&delta| delta as f32).sum::<f32>() / n as f32,
+        };
+        (max_swing, self.score(), mean)
+    }
+
+    /// A quiescence-stabilized `effective_score`: a negamax over noisy moves only, up to the given depth, where the
+    /// player to move may always stand pat on the static evaluation instead of making a noisy move.
+    ///
+    /// This is independent of the search strategy, so it is suitable for labelling positions.
+    pub fn quiescent_score(&self, max_depth: usize) -> i16 {
+        self._quiescence(max_depth, -i16::MAX, i16::MAX)
+    }
+
+    /// A one-ply minimax policy: plays the move that minimizes the best static evaluation the opponent can reach with
+    /// their reply. Ties are broken towards the earliest move in generation order.
+    pub fn best_defensive_move(&self) -> Option<usize> {
+        let mut moves = vec![];
+        self.valid_moves(&mut moves);
+
+        let mut best: Option<(usize, i16)> = None;
+        for mv in moves {
+            let child = self._after(mv);
+
+            let mut replies = vec![];
+            child.valid_moves(&mut replies);
+            let threat = replies.into_iter()
+                .map(|reply| -child._after(reply).effective_score()) // the grandchild is evaluated from our perspective
+                .max()
+                .unwrap_or_else(|| child.effective_score());
+
+            if best.is_none_or(|(_, v)| threat < v) {
+                best = Some((mv, threat));
+            }
+        }
+        best.map(|(mv, _)| mv)
+    }
+
+    /// Clones this board and plays the given move (which may be the swap) on the copy.
+    fn _after(&self, mv: usize) -> Board<'a> {
+        let mut child = self.clone();
+        match mv {
+            NULL_MOVE => child.pass_unchecked_engine(),
+            _         => child.play_unchecked_engine(mv),
+        };
+        child
+    }
+
+    fn _quiescence(&self, depth: usize, mut alpha: i16, beta: i16) -> i16 {
+        let stand_pat = self.effective_score();
+        if depth == 0 || stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut moves = vec![];
+        self.noisy_moves(&mut moves);
+
+        let mut best = stand_pat;
+        for mv in moves {
+            let value = -self._after(mv)._quiescence(depth - 1, -beta, -alpha);
+            if value > best {
+                best = value;
+                alpha = alpha.max(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
     /// The heuristic score on the board from X's perspective.
     /// This heuristic takes into account:
     /// 1. the uncovered scoring tiles protected by foursquare
@@ -26,10 +161,7 @@ impl<'a> Board<'a> {
         let mut constraint = 0i16;
 
         // Unreachable tiles implicated in scoring.
-        let unreachable_symbols = self.unreachable.intersect(&self.symbols);
-
-        // Protected by foursquare, and not covered by a piece.
-        let protected_uncovered = self.protected.difference(&self.cover);
+        let unreachable_symbols = self._unreachable_cells().intersect(&self.symbols);
 
         // Uncovered neighbours to played pieces that are implicated in scoring.
         let neighbour_symbols = self.neighbours.intersect(&self.symbols);
@@ -40,7 +172,7 @@ impl<'a> Board<'a> {
         }
 
         for coord in neighbour_symbols.iter() {
-            let is_protected = protected_uncovered.contains(&coord);
+            let is_protected = self.protected_uncovered.contains(&coord);
             let player = self.get_unchecked(&coord).cell_value().unwrap();
             let value = player.perspective();
 
@@ -56,12 +188,31 @@ impl<'a> Board<'a> {
             }
         }
 
-        material +
-         50 * unreachable_score +
-         25 * security +
-        -15 * threat +
-         10 * connectivity +
-         -5 * constraint
+        let clamp = self.options.eval_clamp.unwrap_or(i16::MAX);
+        Board::_weighted_sum([material, unreachable_score, security, threat, connectivity, constraint], clamp)
+    }
+
+    /// The uncovered cells no future piece can cover: the incrementally-maintained approximation, or, under the exact
+    /// endgame option and with few enough pieces left, the complement of `reachable_cells`.
+    pub(super) fn _unreachable_cells(&self) -> CoordSet {
+        match self.options.exact_endgame {
+            Some(threshold) if self.pieces_remaining() <= threshold => {
+                let mut unreachable = self.reachable_cells().union(&self.cover);
+                unreachable.neg_inplace();
+                unreachable
+            },
+            _ => self.unreachable
+        }
+    }
+
+    /// Combines the heuristic terms under `EVAL_WEIGHTS`, saturating rather than wrapping, and clamps the result into
+    /// `[-clamp, clamp]` so that it can always be negated safely.
+    pub(super) fn _weighted_sum(terms: [i16; 6], clamp: i16) -> i16 {
+        let clamp = clamp.max(0);
+        terms.iter()
+            .zip(EVAL_WEIGHTS)
+            .fold(0i16, |acc, (&term, weight)| acc.saturating_add(term.saturating_mul(weight)))
+            .clamp(-clamp, clamp)
     }
 
 }
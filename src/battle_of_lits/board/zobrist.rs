@@ -6,9 +6,11 @@ use crate::{prelude::{Player, BOARD_SIZE, NUM_PIECES}, battle_of_lits::board::{b
 use super::Board;
 
 const NUM_CELLS: usize = BOARD_SIZE * BOARD_SIZE * 3;
+const NUM_TILES: usize = BOARD_SIZE * BOARD_SIZE * 4;
 
 static ZOBRIST_CELL_TABLE: OnceLock<[u64; NUM_CELLS]> = OnceLock::new();
 static ZOBRIST_MOVE_TABLE: OnceLock<[u64; NUM_PIECES]> = OnceLock::new();
+static ZOBRIST_TILE_TABLE: OnceLock<[u64; NUM_TILES + 1]> = OnceLock::new();
 
 impl<'a> Board<'a> {
     /// Gets the hash for a given Player on a board tile. This hash is _always_ updated as a part of a mutable operation on the grid.
@@ -43,6 +45,44 @@ impl<'a> Board<'a> {
         table[mv]
     }
 
+    /// Gets the hash for a LITS tile on a board cell; the extra final entry keys O to move. Only `position_hash` uses these.
+    fn tile_hash(index: usize) -> u64 {
+        let table = ZOBRIST_TILE_TABLE.get_or_init(|| {
+            let mut table: [u64; NUM_TILES + 1] = [0; NUM_TILES + 1];
+            let mut hasher = DefaultHasher::new();
+            for (i, entry) in table.iter_mut().enumerate() {
+                hasher.write_usize(i + NUM_PIECES + NUM_CELLS);
+                *entry = hasher.finish();
+            }
+            table
+        });
+        table[index]
+    }
+
+    /// Hashes only what is on the board - symbols, tiles and the side to move - rather than which moves produced it.
+    /// Unlike `zobrist`, this is recomputed from scratch on each call.
+    pub fn position_hash(&self) -> u64 {
+        let mut h = if self.player_to_move == Player::X { 0 } else { Board::tile_hash(NUM_TILES) };
+        for (i, row) in self.cells.0.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                h ^= Board::cell_hash(i, j, *cell);
+                if let Some(tile) = cell.lits_value() {
+                    h ^= Board::tile_hash((tile as usize) * BOARD_SIZE * BOARD_SIZE + (i * BOARD_SIZE) + j);
+                }
+            }
+        }
+        h
+    }
+
+    /// Gets the key this board is stored under in the transposition table: `position_hash` if the board options ask
+    /// for position-keyed tables, and the incremental `zobrist` otherwise.
+    pub fn transposition_key(&self) -> u64 {
+        match self.options.position_keyed_table {
+            true  => self.position_hash(),
+            false => self.zobrist_hash
+        }
+    }
+
     /// Given an initial grid, calculates the zobrist hash for the board as if no pieces have been played.
     pub(super) fn initial_zobrist_hash(cells: &Grid) -> u64 {
         let mut h = 0;
@@ -54,3 +94,16 @@ impl<'a> Board<'a> {
         h
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::battle_of_lits::testing::*;
+
+    #[test]
+    fn position_hash_ignores_move_order() {
+        let first = board(&format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]; L[14,15,16,26]"));
+        let second = board(&format!("{SETUP}; I[38,37,36,35]; L[14,15,16,26]; L[53,54,55,45]"));
+        assert_eq!(first.position_hash(), second.position_hash());
+        assert_ne!(first.position_hash(), board(&format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]")).position_hash());
+    }
+}
@@ -21,12 +21,23 @@ impl std::str::FromStr for Coord {
     }
 }
 
+impl std::fmt::Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.row, self.col)
+    }
+}
+
 impl Coord {
     /// Determines whether or not the coord is in bounds.
     pub fn in_bounds(&self) -> bool {
         self.row < 10 && self.col < 10
     }
 
+    /// Gets the row-major index of the coord in the grid.
+    pub fn linear(&self) -> usize {
+        self.row * BOARD_SIZE + self.col
+    }
+
     /// Constructs a new coord.
     pub fn new(row: usize, col: usize) -> Coord {
         Coord { row, col }
@@ -34,7 +45,7 @@ impl Coord {
 
     /// The canonical notation of the coord is its linear offset in the grid.
     pub fn notate(&self) -> String {
-        format!("{}{}", self.row, self.col)
+        self.to_string()
     }
 
     /// Gets the squared distance between the two coords.
@@ -68,6 +79,12 @@ pub static ANCHOR_OFFSETS: [OffsetCoord; 4] = [
     OffsetCoord { rows: 0, cols: 0 },
 ];
 
+impl std::fmt::Display for OffsetCoord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.rows, self.cols)
+    }
+}
+
 impl OffsetCoord {
     /// Coerces the offset into a coordinate unchecked.
     pub fn coerce(&self) -> Coord {
@@ -248,3 +265,15 @@ impl Sub<&OffsetCoord> for OffsetCoord {
         &self - rhs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(Coord::new(0, 7).to_string(), "07");
+        assert_eq!(Coord::new(9, 3).to_string(), Coord::new(9, 3).notate());
+        assert_eq!(OffsetCoord::new(-1, 2).to_string(), "(-1,2)");
+    }
+}
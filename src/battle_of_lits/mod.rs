@@ -10,11 +10,14 @@ pub(crate) mod piecemap;
 pub mod sets;
 pub(crate) mod tetromino;
 
+#[cfg(test)]
+pub(crate) mod testing;
+
 pub mod prelude {
     pub(crate) use crate::utils::prelude::*;
 
     pub use super::{
-        board::Board,
+        board::{Board, BoardOptions, GameResult, Phase, PlayOutcome},
         consts::*,
         coords::{self, *},
         notation::*,
@@ -12,7 +12,7 @@ pub struct SetupString {
 }
 
 /// Ensures a produced grid is actually valid; i.e. Xs and Os have rotational equivalence.
-fn _validate_rotational_symmetry(grid: &Grid) -> std::result::Result<(), Error> {
+pub(crate) fn _validate_rotational_symmetry(grid: &Grid) -> std::result::Result<(), Error> {
     for r in 0..BOARD_SIZE {
         for c in 0..BOARD_SIZE {
             let lhs = grid.0[r][c].cell_value();
@@ -26,10 +26,47 @@ fn _validate_rotational_symmetry(grid: &Grid) -> std::result::Result<(), Error>
     Ok(())
 }
 
-/// Parses the 20-character bitstring encoding for the game.
-fn _parse_compressed_setup_string(_s: &str) -> std::result::Result<SetupString, Error> {
-    let _grid = Grid::default();
-    todo!("parse a compressed string... 3 days later, I have no motivation to implement this")
+/// The alphabet for compressed setup strings; each character carries a quintet of bits.
+const COMPRESSED_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+/// Produces the 20-character bitstring encoding for a grid, un-negating the symbols if a swap was played.
+/// 
+/// Each of the 100 bits (in row-major order, most significant bit first within each quintet) marks a cell holding an X;
+/// by rotational symmetry, this is enough to recover every O.
+pub(crate) fn _compress_grid(grid: &Grid, was_swapped: bool) -> String {
+    let bits = grid.0.as_flattened().iter().map(|cell| {
+        cell.cell_value().is_some_and(|v| (if was_swapped { -v } else { v }) == Player::X)
+    }).collect::<Vec<bool>>();
+
+    bits.chunks(5).map(|quintet| {
+        let value = quintet.iter().fold(0usize, |acc, &bit| (acc << 1) | bit as usize);
+        COMPRESSED_ALPHABET[value] as char
+    }).collect()
+}
+
+/// Parses the 20-character bitstring encoding for the game; see `_compress_grid`.
+fn _parse_compressed_setup_string(s: &str) -> std::result::Result<SetupString, Error> {
+    let mut grid = Grid::default();
+    for (i, ch) in s.chars().enumerate() {
+        let Some(value) = COMPRESSED_ALPHABET.iter().position(|&c| c as char == ch.to_ascii_uppercase()) else {
+            return Err(anyhow!("invalid character {ch} in compressed setup string {s}"));
+        };
+        for bit in 0..5 {
+            if (value >> (4 - bit)) & 1 == 0 {
+                continue;
+            }
+            let index = i * 5 + bit;
+            let [r, c] = [index / BOARD_SIZE, index % BOARD_SIZE];
+            let [mr, mc] = [BOARD_SIZE - 1 - r, BOARD_SIZE - 1 - c];
+            if grid.0[r][c].cell_value().is_some() {
+                return Err(anyhow!("cells {}{} and {}{} are both marked as X", r, c, mr, mc));
+            }
+            grid.0[r][c] = grid.0[r][c].with_cell(Some(Player::X));
+            grid.0[mr][mc] = grid.0[mr][mc].with_cell(Some(Player::O));
+        }
+    }
+    _validate_rotational_symmetry(&grid)?;
+    Ok(SetupString { repr: s.to_owned(), grid })
 }
 
 /// Parses a 100-character setup string (of the form XO..X.X.O. etc.).
@@ -44,6 +81,13 @@ fn _parse_naive_setup_string(s: &str) -> std::result::Result<SetupString, Error>
     Ok(SetupString { repr: s.to_owned(), grid })
 }
 
+impl SetupString {
+    /// Produces the 20-character compressed form of this setup.
+    pub fn compress(&self) -> String {
+        _compress_grid(&self.grid, false)
+    }
+}
+
 impl std::str::FromStr for SetupString {
     type Err = Error;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
@@ -128,3 +172,27 @@ impl std::str::FromStr for GameString {
         Ok(GameString { setup, moves })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle_of_lits::testing::SETUP;
+
+    #[test]
+    fn compressed_setup_roundtrips() {
+        let naive = SETUP.parse::<SetupString>().unwrap();
+        let compressed = naive.compress();
+        assert_eq!(compressed.len(), 20);
+
+        let recovered = compressed.parse::<SetupString>().unwrap();
+        assert_eq!(recovered.grid.notate(false), SETUP);
+        assert_eq!(recovered.compress(), compressed);
+    }
+
+    #[test]
+    fn compressed_setup_rejects_clashing_xs() {
+        let clash = format!("G{}1", "0".repeat(18)); // X at 00 and at 99
+        assert!(clash.parse::<SetupString>().is_err());
+        assert!("W0000000000000000000".parse::<SetupString>().is_err()); // not in the alphabet
+    }
+}
@@ -22,7 +22,7 @@ pub fn compute_connectivity_bridges(piece: &Tetromino) -> Vec<(Coord, Coord)> {
         }
     }
     
-    let neighbors_vec: Vec<Coord> = neighbors.iter().collect();
+    let neighbors_vec = neighbors.to_sorted_vec();
 
     // Find pairs of neighbors that this piece bridges together
     for i in 0..neighbors_vec.len() {
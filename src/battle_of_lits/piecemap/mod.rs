@@ -42,6 +42,9 @@ pub struct PieceMap {
     /// Get the neighbouring coords to the tetromino.
     neighbours: Box<[CoordSet; NUM_PIECES]>,
 
+    /// The neighbouring coords to the tetromino, packed as in `CoordSet::to_u128` for hot loops over raw integers.
+    neighbours_u128: Box<[u128; NUM_PIECES]>,
+
     /// Get the coordset representation of a piece instead of the array representation.
     selfs: Box<[CoordSet; NUM_PIECES]>,
 
@@ -69,12 +72,22 @@ pub struct PieceMap {
     /// Precomputed as coordset(id) ∪ neighbours(id) for each piece.
     shadowsets: Box<[CoordSet; NUM_PIECES]>,
 
+    /// The pieces covering or bordering each on-board coord; the inverse of `shadowsets`.
+    touching: Box<[MoveSet; 100]>,
+
     /// Sets of all pieces by tile type (L, I, T, S).
     /// Used for fast filtering by piece bag availability.
     pieces_by_type: [MoveSet; 4],
 }
 
 impl PieceMap {
+    /// Gets the anchor of a piece, without copying the whole tetromino.
+    pub fn anchor_of(&self, id: usize) -> Coord {
+        unsafe {
+            self.forward.get_unchecked(id).anchor
+        }
+    }
+
     /// Gets a coordset consisting of the on-board neighbours of an on-board Coord.
     pub fn coord_neighbours(&self, coord: &Coord) -> &CoordSet {
         unsafe {
@@ -133,6 +146,13 @@ impl PieceMap {
         }
     }
 
+    /// Gets the set of all pieces that cover or border an on-board coord.
+    pub fn pieces_touching(&self, coord: &Coord) -> &MoveSet {
+        unsafe {
+            self.touching.get_unchecked(coord.linear())
+        }
+    }
+
     /// Gets the set of all pieces of a given tile type.
     pub fn pieces_of_type(&self, tile: Tile) -> &MoveSet {
         &self.pieces_by_type[tile as usize]
@@ -168,10 +188,16 @@ impl PieceMap {
         if let Some(&id) = self.reverse.get(&v) {
             Ok(id)
         } else {
-            Err(anyhow!("id {coords:?} out of range"))
+            Err(anyhow!("no piece covers {}", coords.iter().join(", ")))
         }
     }
 
+    /// Gets the id of the image of a piece under a board transform.
+    pub fn transformed(&self, id: usize, transform: &Transform) -> usize {
+        let coords = self.get_piece(id).real_coords().map(|c| transform.apply_board(&c.coerce()).into());
+        self.try_and_find(&coords).expect("board transforms keep pieces in bounds")
+    }
+
     /// Gets the piece neighbours as a coordset.
     pub fn neighbours(&self, id: usize) -> &CoordSet {
         unsafe {
@@ -179,6 +205,13 @@ impl PieceMap {
         }
     }
 
+    /// Gets the piece neighbours as a packed integer; see `CoordSet::to_u128`.
+    pub fn neighbours_u128(&self, id: usize) -> u128 {
+        unsafe {
+            *self.neighbours_u128.get_unchecked(id)
+        }
+    }
+
     /// Validates a piece id.
     pub fn get_piece_checked(&self, id: usize) -> Result<Tetromino> {
         if id < NUM_PIECES {
@@ -196,10 +229,43 @@ impl PieceMap {
         }
     }
 
+    /// Gets the transform of a piece relative to its kind's identity shape, without copying the whole tetromino.
+    pub fn transform_of(&self, id: usize) -> Transform {
+        unsafe {
+            self.forward.get_unchecked(id).transform
+        }
+    }
+
     /// Gets the interactions on a piece matching a certain outcome.
     pub fn with_interaction(&self, id: usize, interaction: Interaction) -> &MoveSet {
         unsafe {
             self.associations_specific.get_unchecked(id).get_unchecked(interaction as usize)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::battle_of_lits::prelude::*;
+    use crate::battle_of_lits::testing::*;
+
+    #[test]
+    fn accessors_match_the_piece() {
+        let piecemap = piecemap();
+        for id in [0, 1, 333, 1291] {
+            let piece = piecemap.get_piece(id);
+            assert_eq!(piecemap.anchor_of(id), piece.anchor);
+            assert_eq!(piecemap.transform_of(id), piece.transform);
+        }
+    }
+
+    #[test]
+    fn packed_neighbours_match_the_coordset() {
+        let piecemap = piecemap();
+        for id in 0..NUM_PIECES {
+            let packed = piecemap.neighbours_u128(id);
+            assert_eq!(packed.count_ones() as usize, piecemap.neighbours(id).len());
+            assert_eq!(CoordSet::from_u128(packed).to_u128(), piecemap.neighbours(id).to_u128());
+        }
+    }
+}
@@ -85,6 +85,8 @@ impl PieceMap {
             neighbours.assume_init()
         };
 
+        let neighbours_u128 = Box::new(std::array::from_fn(|idx| neighbours[idx].to_u128()));
+
         let selfs = unsafe {
             let mut selfs: Box<MaybeUninit<[CoordSet; NUM_PIECES]>> = Box::new_zeroed();
             (0..NUM_PIECES).for_each(|idx| {
@@ -160,6 +162,11 @@ impl PieceMap {
             shadowsets.assume_init()
         };
 
+        let touching = Box::new(std::array::from_fn(|idx| {
+            let coord = Coord { row: idx / BOARD_SIZE, col: idx % BOARD_SIZE };
+            (0..NUM_PIECES).filter(|&id| shadowsets[id].contains(&coord)).collect()
+        }));
+
         let pieces_by_type = {
             let mut sets = [MoveSet::default(); 4];
             for idx in 0..NUM_PIECES {
@@ -176,6 +183,7 @@ impl PieceMap {
             associations_specific,
             coord_neighbours,
             neighbours,
+            neighbours_u128,
             selfs,
             chokepoints,
             bridges,
@@ -183,6 +191,7 @@ impl PieceMap {
             connectivity_dependencies,
             isolation_shadows,
             shadowsets,
+            touching,
             pieces_by_type
         }
     }
@@ -143,13 +143,14 @@ fn find_isolated_region_from_barrier(barrier: &CoordSet, anchor: &Coord) -> Coor
     // Start flood fill from positions that might be cut off
     let potential_seeds = get_potential_isolation_seeds(barrier, anchor);
     
+    let mut component = CoordSet::default();
     for seed in potential_seeds {
         if isolated_region.contains(&seed) || barrier.contains(&seed) {
             continue;
         }
         
         // Flood fill to find connected component
-        let component = flood_fill_component(&seed, barrier);
+        flood_fill_component(&seed, barrier, &mut component);
         
         // Check if this component is isolated (can't reach board edges)
         if is_component_isolated(&component, barrier) {
@@ -164,7 +165,7 @@ fn find_isolated_region_from_barrier(barrier: &CoordSet, anchor: &Coord) -> Coor
 
 /// Gets potential starting points for isolation detection.
 fn get_potential_isolation_seeds(barrier: &CoordSet, _anchor: &Coord) -> Vec<Coord> {
-    let mut seeds = Vec::new();
+    let mut seeds = CoordSet::default();
     
     // Check areas adjacent to the barrier that might be cut off
     for coord in barrier.iter() {
@@ -174,18 +175,18 @@ fn get_potential_isolation_seeds(barrier: &CoordSet, _anchor: &Coord) -> Vec<Coo
                 let candidate_coord = candidate.coerce();
                 
                 if !barrier.contains(&candidate_coord) {
-                    seeds.push(candidate_coord);
+                    seeds.insert(&candidate_coord);
                 }
             }
         }
     }
     
-    seeds
+    seeds.to_sorted_vec()
 }
 
-/// Performs flood fill to find a connected component.
-fn flood_fill_component(start: &Coord, barrier: &CoordSet) -> CoordSet {
-    let mut component = CoordSet::default();
+/// Performs flood fill to find a connected component, clearing `component` first so that one set serves every seed.
+fn flood_fill_component(start: &Coord, barrier: &CoordSet, component: &mut CoordSet) {
+    component.clear();
     let mut stack = vec![*start];
     
     while let Some(coord) = stack.pop() {
@@ -206,8 +207,6 @@ fn flood_fill_component(start: &Coord, barrier: &CoordSet) -> CoordSet {
             }
         }
     }
-    
-    component
 }
 
 /// Determines if a component is isolated (cannot reach board edges).
@@ -12,10 +12,31 @@ pub struct CoordSet([SubSet; NUM_SUBSETS]);
 // Mask for the second u64 to zero out unused bits (36-63)
 const EXTENT_MASK: SubSet = (1u64 << (BOARD_CELLS - 64)) - 1; // Mask for bits 0-35
 
+// Packed (as in `CoordSet::to_u128`) masks for the whole board and its outer columns, so shifts don't wrap across rows
+const ALL_CELLS: u128 = (1u128 << BOARD_CELLS) - 1;
+const FIRST_COL: u128 = column_mask(0);
+const LAST_COL: u128 = column_mask(BOARD_SIZE - 1);
+
+const fn column_mask(col: usize) -> u128 {
+    let mut mask = 0;
+    let mut row = 0;
+    while row < BOARD_SIZE {
+        mask |= 1u128 << (row * BOARD_SIZE + col);
+        row += 1;
+    }
+    mask
+}
+
+/// Grows a packed set by one orthogonal step.
+#[inline]
+fn expand_bits(bits: u128) -> u128 {
+    (bits | (bits & !LAST_COL) << 1 | (bits & !FIRST_COL) >> 1 | bits << BOARD_SIZE | bits >> BOARD_SIZE) & ALL_CELLS
+}
+
 impl CoordSet {
     #[inline]
     fn _index(coord: &Coord) -> (usize, usize) {
-        let linear_index = coord.row * BOARD_SIZE + coord.col;
+        let linear_index = coord.linear();
         (linear_index / 64, linear_index % 64)
     }
 
@@ -25,6 +46,57 @@ impl CoordSet {
         self
     }
 
+    /// Empties the set in place, so that an accumulator can be reused without constructing a new one.
+    #[inline]
+    pub fn clear(&mut self) -> &mut Self {
+        self.0.fill(0);
+        self
+    }
+
+    /// Packs the set into a single integer, where bit `i` is the cell with linear index `i`.
+    #[inline]
+    pub fn to_u128(&self) -> u128 {
+        (self.0[1] as u128) << 64 | self.0[0] as u128
+    }
+
+    /// Unpacks a set from its `CoordSet::to_u128` representation, ignoring bits past the last cell.
+    #[inline]
+    pub fn from_u128(bits: u128) -> CoordSet {
+        CoordSet([bits as SubSet, (bits >> 64) as SubSet & EXTENT_MASK])
+    }
+
+    /// Grows the set by one orthogonal step, i.e. adds every on-board neighbour of every cell.
+    #[inline]
+    pub fn expand(&self) -> CoordSet {
+        CoordSet::from_u128(expand_bits(self.to_u128()))
+    }
+
+    /// Finds every cell connected to the seed through cells outside the barrier, by dilating the seed until it stops
+    /// growing; this is a bitwise alternative to a stack-based flood fill. Seed cells in the barrier are dropped.
+    pub fn reachable_closure(seed: &CoordSet, barrier: &CoordSet) -> CoordSet {
+        let open = !barrier.to_u128() & ALL_CELLS;
+        let mut region = seed.to_u128() & open;
+        loop {
+            let grown = expand_bits(region) & open;
+            if grown == region {
+                return CoordSet::from_u128(region);
+            }
+            region = grown;
+        }
+    }
+
+    /// Collects the set into a vector of coords in ascending (row-major) order. Iteration already visits coords in this
+    /// order, but callers whose output depends on it should use this rather than rely on the iterator.
+    pub fn to_sorted_vec(&self) -> Vec<Coord> {
+        self.iter().collect()
+    }
+
+    /// Iterates over the set, pairing each coord with its linear index (as in `Coord::linear`) in ascending order.
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, Coord)> {
+        let mut iter = CoordSetIterator::new(&self.0);
+        std::iter::from_fn(move || iter.next_indexed())
+    }
+
     /// Fast check if intersection would be empty without allocating
     #[inline]
     pub fn intersects(&self, other: &Self) -> bool {
@@ -42,6 +114,28 @@ impl CoordSet {
     pub fn count_fast(&self) -> usize {
         self.0[0].count_ones() as usize + self.0[1].count_ones() as usize
     }
+
+    /// Fast count of elements in `self & !other` without allocating the difference; padding bits are masked out.
+    #[inline]
+    pub fn difference_count(&self, other: &Self) -> usize {
+        (self.0[0] & !other.0[0]).count_ones() as usize + (self.0[1] & !other.0[1] & EXTENT_MASK).count_ones() as usize
+    }
+
+    /// Fast count of elements in `self & other` without allocating the intersection.
+    #[inline]
+    pub fn intersect_count(&self, other: &Self) -> usize {
+        (self.0[0] & other.0[0]).count_ones() as usize + (self.0[1] & other.0[1]).count_ones() as usize
+    }
+
+    /// The centroid of the set as (row, col), or None if the set is empty.
+    pub fn center_of_mass(&self) -> Option<(f32, f32)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (rows, cols) = self.iter().fold((0, 0), |(rows, cols), c| (rows + c.row, cols + c.col));
+        let n = self.len() as f32;
+        Some((rows as f32 / n, cols as f32 / n))
+    }
 }
 
 impl Default for CoordSet {
@@ -50,6 +144,24 @@ impl Default for CoordSet {
     }
 }
 
+/// Serializes as the ascending array of member linear indices (see `Coord::linear`).
+impl serde::Serialize for CoordSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter_indexed().map(|(index, _)| index))
+    }
+}
+
+/// Deserializes from an array of linear indices, rejecting any that are off the board.
+impl<'de> serde::Deserialize<'de> for CoordSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let indices = Vec::<usize>::deserialize(deserializer)?;
+        if let Some(index) = indices.iter().find(|&&index| index >= BOARD_CELLS) {
+            return Err(serde::de::Error::custom(format!("cell index {index} is off the board")));
+        }
+        Ok(indices.into_iter().map(|index| Coord::new(index / BOARD_SIZE, index % BOARD_SIZE)).collect())
+    }
+}
+
 impl SetOps<&Coord, Coord> for CoordSet {
     fn contains(&self, value: &Coord) -> bool {
         let (ia, ib) = CoordSet::_index(value);
@@ -82,6 +194,15 @@ impl SetOps<&Coord, Coord> for CoordSet {
         self
     }
 
+    fn toggle(&mut self, value: &Coord) -> &mut Self {
+        let (ia, ib) = CoordSet::_index(value);
+        let v = (1 as SubSet) << ib;
+        unsafe {
+            *self.0.get_unchecked_mut(ia) ^= v;
+        }
+        self
+    }
+
     fn _extend(&mut self, iter: impl Iterator<Item = Coord>) -> &mut Self {
         iter.into_iter().for_each(|c| {
             self.insert(&c);
@@ -170,11 +291,9 @@ impl<'a> CoordSetIterator<'a> {
     pub fn new<'d>(data: &'d [SubSet; NUM_SUBSETS]) -> CoordSetIterator<'d> {
         CoordSetIterator { data, mask: SubSet::MAX, current_subset: 0 }
     }
-}
 
-impl<'a> Iterator for CoordSetIterator<'a> {
-    type Item = Coord;
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Advances the iterator, also yielding the linear index it already computed for the coord.
+    pub fn next_indexed(&mut self) -> Option<(usize, Coord)> {
         loop {
             if self.current_subset >= NUM_SUBSETS {
                 return None;
@@ -198,12 +317,19 @@ impl<'a> Iterator for CoordSetIterator<'a> {
                 let col = linear_index % BOARD_SIZE;
                 let value = Coord::new(row, col);
                 self.mask ^= (1 as SubSet) << tz;
-                return Some(value);
+                return Some((linear_index, value));
             }
         }
     }
 }
 
+impl<'a> Iterator for CoordSetIterator<'a> {
+    type Item = Coord;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_indexed().map(|(_, coord)| coord)
+    }
+}
+
 pub struct CoordSetIntoIterator {
     data: [SubSet; NUM_SUBSETS],
     mask: SubSet,
@@ -355,3 +481,143 @@ impl CoordSet {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn to_sorted_vec_is_ascending() {
+        let set: CoordSet = [(9, 9), (0, 5), (4, 0), (3, 9), (0, 4)].map(|(row, col)| Coord::new(row, col)).into_iter().collect();
+        let sorted = set.to_sorted_vec();
+
+        assert_eq!(sorted, vec![Coord::new(0, 4), Coord::new(0, 5), Coord::new(3, 9), Coord::new(4, 0), Coord::new(9, 9)]);
+        assert!(sorted.is_sorted());
+        assert!(CoordSet::default().to_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn u128_roundtrips() {
+        let set: CoordSet = [(0, 0), (6, 3), (6, 4), (9, 9)].map(|(row, col)| Coord::new(row, col)).into_iter().collect();
+        let bits = set.to_u128();
+
+        assert_eq!(bits.count_ones() as usize, set.len());
+        assert!(set.iter().all(|coord| bits >> coord.linear() & 1 == 1));
+        assert_eq!(CoordSet::from_u128(bits).to_u128(), bits);
+        assert_eq!(CoordSet::from_u128(u128::MAX).len(), BOARD_SIZE * BOARD_SIZE);
+    }
+
+    #[test]
+    fn expand_stays_on_the_board() {
+        let corners: CoordSet = [(0, 0), (0, 9), (9, 0), (9, 9)].map(|(row, col)| Coord::new(row, col)).into_iter().collect();
+        assert_eq!(corners.expand().len(), 12);
+
+        let edge: CoordSet = [Coord::new(3, 9)].into_iter().collect();
+        let grown = edge.expand();
+        assert_eq!(grown.to_sorted_vec(), vec![Coord::new(2, 9), Coord::new(3, 8), Coord::new(3, 9), Coord::new(4, 9)]);
+    }
+
+    #[test]
+    fn reachable_closure_matches_flood_fill() {
+        let piecemap = crate::battle_of_lits::testing::piecemap();
+        let flood = |seed: &CoordSet, barrier: &CoordSet| {
+            let mut region = CoordSet::default();
+            let mut stack = seed.difference(barrier).to_sorted_vec();
+            while let Some(coord) = stack.pop() {
+                if region.contains(&coord) {
+                    continue;
+                }
+                region.insert(&coord);
+                stack.extend(piecemap.coord_neighbours(&coord).difference(barrier).difference(&region).iter());
+            }
+            region
+        };
+
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut cases = vec![];
+        for _ in 0..500 {
+            let mut bits = [0u128; 2];
+            for word in &mut bits {
+                state ^= state << 13; state ^= state >> 7; state ^= state << 17;
+                let lo = state;
+                state ^= state << 13; state ^= state >> 7; state ^= state << 17;
+                *word = (state as u128) << 64 | lo as u128;
+            }
+            let barrier = CoordSet::from_u128(bits[0] & bits[1]); // roughly a quarter of the board
+            let seed = CoordSet::from_u128(1u128 << (state % 100));
+            cases.push((seed, barrier));
+        }
+
+        for (seed, barrier) in &cases {
+            assert_eq!(CoordSet::reachable_closure(seed, barrier).to_u128(), flood(seed, barrier).to_u128());
+        }
+    }
+
+    #[test]
+    fn toggling_twice_restores_the_set() {
+        let (a, b) = (Coord::new(2, 3), Coord::new(8, 1));
+        let mut set = CoordSet::from_iter([a]);
+        let before = set.to_u128();
+
+        set.toggle(&a).toggle(&b);
+        assert!(!set.contains(&a) && set.contains(&b));
+        set.toggle(&b).toggle(&a);
+        assert_eq!(set.to_u128(), before);
+    }
+
+    #[test]
+    fn serde_roundtrips_as_indices() {
+        let set: CoordSet = [(0, 0), (3, 7), (9, 9)].map(|(row, col)| Coord::new(row, col)).into_iter().collect();
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, "[0,37,99]");
+        assert_eq!(serde_json::from_str::<CoordSet>(&json).unwrap().to_u128(), set.to_u128());
+
+        assert!(serde_json::from_str::<CoordSet>("[5,100]").is_err());
+        assert!(serde_json::from_str::<CoordSet>("[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn iter_indexed_matches_linear() {
+        let set: CoordSet = [(0, 0), (3, 7), (6, 3), (9, 9)].map(|(row, col)| Coord::new(row, col)).into_iter().collect();
+        let indexed = set.iter_indexed().collect::<Vec<_>>();
+
+        assert_eq!(indexed.len(), set.len());
+        assert!(indexed.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(indexed.iter().all(|(index, coord)| *index == coord.linear()));
+        assert!(indexed.iter().map(|&(_, coord)| coord).eq(set.iter()));
+    }
+
+    #[test]
+    fn counts_match_set_operations() {
+        let a: CoordSet = (0..BOARD_SIZE).map(|i| Coord::new(i, i)).chain([Coord::new(9, 0), Coord::new(0, 9)]).collect();
+        let mut b: CoordSet = (0..BOARD_SIZE).map(|i| Coord::new(i, 9 - i)).collect();
+
+        assert_eq!(a.difference_count(&b), a.difference(&b).len());
+        assert_eq!(a.intersect_count(&b), a.intersect(&b).len());
+
+        b.neg_inplace(); // complements only touch real cells, never the padding
+        assert_eq!(a.difference_count(&b), a.difference(&b).len());
+        assert_eq!(a.intersect_count(&b), a.intersect(&b).len());
+    }
+
+    #[test]
+    fn clear_empties_and_reuses() {
+        let mut s = CoordSet::default();
+        s.neg_inplace();
+        s.clear();
+        assert!(s.is_empty());
+        assert_eq!(s.count_fast(), 0);
+
+        s.insert(&Coord::new(4, 2));
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![Coord::new(4, 2)]);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        assert_eq!(CoordSet::default().center_of_mass(), None);
+
+        let corners = [(0, 0), (0, 9), (9, 0), (9, 9)].map(|(row, col)| Coord::new(row, col));
+        let set = CoordSet::from_iter(corners.iter());
+        assert_eq!(set.center_of_mass(), Some((4.5, 4.5)));
+    }
+}
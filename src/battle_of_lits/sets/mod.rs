@@ -28,6 +28,9 @@ pub trait SetOps<T: Clone + Copy + std::fmt::Debug, I> {
     /// Removes a value from the set, if it exists.
     fn remove(&mut self, value: T) -> &mut Self;
 
+    /// Inserts a value into the set if it is absent, or removes it if it is present.
+    fn toggle(&mut self, value: T) -> &mut Self;
+
     /// Extends the set from an iterator of compatible values.
     fn _extend(&mut self, iter: impl Iterator<Item = I>) -> &mut Self;
 
@@ -1,5 +1,5 @@
 
-use crate::prelude::{SetOps, NUM_PIECES};
+use crate::prelude::{PieceMap, SetOps, Tile, NULL_MOVE, NUM_PIECES};
 use itertools::Itertools;
 
 type SubSet = u64;
@@ -41,6 +41,44 @@ impl MoveSet {
         
         set
     }
+
+    /// Counts the pieces of each kind (in `Tile::all` order) in the set; the null move belongs to no kind.
+    pub fn count_by_kind(&self, piecemap: &PieceMap) -> [usize; 4] {
+        Tile::all().map(|tile| self.intersect(piecemap.pieces_of_type(tile)).len())
+    }
+
+    /// Empties the set in place, so that an accumulator can be reused without constructing a new one.
+    #[inline]
+    pub fn clear(&mut self) -> &mut Self {
+        self.0.fill(0);
+        self
+    }
+
+    /// Picks an element of the set uniformly at random, if there is one.
+    pub fn random(&self, rng: &mut impl rand::Rng) -> Option<usize> {
+        match self.len() {
+            0   => None,
+            len => self.nth(rng.random_range(0..len)),
+        }
+    }
+
+    /// Finds the k-th smallest element in the set by skipping whole subsets via their popcounts, then clearing the
+    /// lowest set bits of the containing subset until the k-th one is at the bottom.
+    pub fn nth(&self, k: usize) -> Option<usize> {
+        let mut remaining = k;
+        for (i, &subset) in self.0[..NUM_SUBSETS].iter().enumerate() {
+            let count = subset.count_ones() as usize;
+            if remaining < count {
+                let mut bits = subset;
+                for _ in 0..remaining {
+                    bits &= bits - 1; // knock out the lowest set bit
+                }
+                return Some(i * SUBSET_SIZE + bits.trailing_zeros() as usize);
+            }
+            remaining -= count;
+        }
+        None
+    }
 }
 
 impl Default for MoveSet {
@@ -49,6 +87,24 @@ impl Default for MoveSet {
     }
 }
 
+/// Serializes as the ascending array of member ids.
+impl serde::Serialize for MoveSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserializes from an array of ids, rejecting any past the null move (which a set of legal moves may contain).
+impl<'de> serde::Deserialize<'de> for MoveSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ids = Vec::<usize>::deserialize(deserializer)?;
+        if let Some(id) = ids.iter().find(|&&id| id > NULL_MOVE) {
+            return Err(serde::de::Error::custom(format!("move id {id} is out of range")));
+        }
+        Ok(ids.into_iter().collect())
+    }
+}
+
 impl SetOps<usize, usize> for MoveSet {
     fn contains(&self, value: usize) -> bool {
         let (ia, ib) = MoveSet::_index(value);
@@ -88,6 +144,15 @@ impl SetOps<usize, usize> for MoveSet {
         self
     }
 
+    fn toggle(&mut self, value: usize) -> &mut Self {
+        let (ia, ib) = MoveSet::_index(value);
+        let v = (1 as SubSet) << ib;
+        unsafe {
+            *self.0.get_unchecked_mut(ia) ^= v;
+        }
+        self
+    }
+
     fn _extend(&mut self, iter: impl Iterator<Item = usize>) -> &mut Self {
         iter.into_iter().for_each(|i| {
             self.insert(i);
@@ -204,7 +269,8 @@ impl<'a> Iterator for MoveSetIterator<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::prelude::SetOps;
+    use crate::battle_of_lits::testing::*;
+    use crate::prelude::{SetOps, Tile, NUM_PIECES};
 
     use super::MoveSet;
     use std::collections::BTreeSet;
@@ -219,6 +285,66 @@ mod tests {
 
         assert!(elements == recovered) 
     }
+
+    #[test]
+    fn nth_matches_iteration() {
+        let mut state: usize = 0x2545;
+        let mut s = MoveSet::default();
+        for _ in 0..300 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            s.insert((state >> 33) % 1293);
+        }
+
+        for k in 0..=s.len() + 1 {
+            assert_eq!(s.nth(k), s.iter().nth(k), "k = {k}");
+        }
+    }
+
+    #[test]
+    fn count_by_kind_partitions_the_set() {
+        let board = board(&format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]"));
+        let moves = board.valid_moves_set();
+        let counts = moves.count_by_kind(piecemap());
+
+        assert_eq!(counts.iter().sum::<usize>(), moves.len());
+        assert_eq!(counts[Tile::I as usize], moves.iter().filter(|&mv| piecemap().get_kind(mv) == Tile::I).count());
+        assert_eq!(MoveSet::all().count_by_kind(piecemap()).iter().sum::<usize>(), NUM_PIECES);
+    }
+
+    #[test]
+    fn clear_empties_and_reuses() {
+        let mut s = MoveSet::all();
+        s.clear();
+        assert!(s.is_empty());
+        assert_eq!(s.iter().next(), None);
+
+        s.insert(7).insert(1292);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![7, 1292]);
+    }
+
+    #[test]
+    fn toggling_twice_restores_the_set() {
+        let mut s = MoveSet::default();
+        s.insert(5).insert(900);
+        let before = s.iter().collect::<Vec<_>>();
+
+        s.toggle(900).toggle(1292);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![5, 1292]);
+        s.toggle(1292).toggle(900);
+        assert_eq!(s.iter().collect::<Vec<_>>(), before);
+    }
+
+    #[test]
+    fn serde_roundtrips_as_ids() {
+        let mut s = MoveSet::default();
+        s.insert(0).insert(321).insert(NUM_PIECES);
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(json, "[0,321,1292]");
+        assert!(serde_json::from_str::<MoveSet>(&json).unwrap().iter().eq(s.iter()));
+
+        assert!(serde_json::from_str::<MoveSet>("[1293]").is_err());
+        assert!(serde_json::from_str::<MoveSet>("[]").unwrap().is_empty());
+    }
 }
 
 impl std::iter::Extend<usize> for MoveSet {
@@ -0,0 +1,34 @@
+//! Shared fixtures for tests that need a built piecemap.
+
+use std::sync::OnceLock;
+
+use super::prelude::*;
+
+/// A setup with no scoring symbols on it.
+pub const BLANK: &str = "....................................................................................................";
+
+/// A setup with a spread of scoring symbols (see `docs/board.txt`).
+pub const SETUP: &str = "XXX........XO.........O.O.............X.X.......OXOX.......O.O.............X.X.........XO........OOO";
+
+/// A setup that is symmetric under transposition, so openings come in mirrored pairs.
+pub const TRANSPOSED: &str = ".X........X............X........X..................................O........O............O........O.";
+
+/// A complete (terminal) game on `SETUP`, won by X by 4.
+pub const FINISHED: &str = "L[27,26,25,35]; S[18,28,29,39]; T[48,47,46,37]; L[57,67,77,78]; S[12,13,23,24]; I[45,55,65,75]; S[72,73,63,64]; L[41,42,43,53]; S[84,85,95,96]; T[99,98,97,88]; L[30,20,10,11]; I[09,08,07,06]; T[80,70,60,71]; T[93,92,91,82]; I[49,59,69,79]";
+
+/// Gets the piecemap, building it once per test binary since construction takes a few seconds.
+pub fn piecemap() -> &'static PieceMap {
+    static PIECEMAP: OnceLock<PieceMap> = OnceLock::new();
+    PIECEMAP.get_or_init(PieceMap::new)
+}
+
+/// Loads a board from a gamestring, playing each of its moves in order.
+pub fn board(gamestr: &str) -> Board<'static> {
+    Board::from_gamestring(&gamestr.parse::<GameString>().unwrap(), piecemap()).unwrap()
+}
+
+/// Gets the id of a piece from its notation.
+pub fn piece(notation: &str) -> usize {
+    let MoveString { repr: _, tetromino } = notation.parse::<MoveString>().unwrap();
+    piecemap().try_and_find(&tetromino.unwrap().real_coords()).unwrap()
+}
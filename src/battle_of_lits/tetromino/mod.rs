@@ -49,6 +49,16 @@ impl Tetromino {
         }
     }
 
+    /// Gets the occupied row indices in the given column, in ascending order (empty if the piece misses the column).
+    pub fn cells_in_col(&self, col: usize) -> Vec<usize> {
+        self.real_coords().iter().filter(|c| c.cols == col as isize).map(|c| c.rows as usize).collect()
+    }
+
+    /// Gets the occupied column indices in the given row, in ascending order (empty if the piece misses the row).
+    pub fn cells_in_row(&self, row: usize) -> Vec<usize> {
+        self.real_coords().iter().filter(|c| c.rows == row as isize).map(|c| c.cols as usize).collect()
+    }
+
     /// Gives back all tetrominos that result from canonical transformations on this Tetromino's anchor and type.
     pub fn enumerate(&self) -> Vec<Tetromino> {
         let transforms = Transform::enumerate(&self.kind);
@@ -56,6 +66,12 @@ impl Tetromino {
         transforms.iter().map(|transform| transform.apply(&iden)).collect()
     }
 
+    /// Constructs the tetromino of the given kind with the given anchor and transform; this is the inverse of a piece's
+    /// `anchor` and `transform`. Makes no guarantees that the tile is in bounds!
+    pub fn from_anchor_transform(kind: Tile, anchor: &Coord, transform: Transform) -> Tetromino {
+        transform.apply(&Tetromino::identity(kind, anchor))
+    }
+
     /// Constructs the identity tetromino at the given anchor. Makes no guarantees that the tile is in bounds!
     pub fn identity(kind: Tile, anchor: &Coord) -> Tetromino {
         let template = Tetromino::_identity_template(kind);
@@ -129,6 +145,30 @@ impl Tetromino {
         }).collect()
     }
 
+    /// Reduces the tetromino to a canonical representative of its symmetry class, such that any two tetrominoes that are
+    /// rotations, reflections or translations of each other normalize to the same value. This is done by:
+    /// 1. applying each of the 8 transforms to the real coordinates
+    /// 2. translating each result so that its minimum row and column are both 0
+    /// 3. keeping the lexicographically smallest (sorted) coordinate set
+    pub fn normalized(&self) -> Tetromino {
+        let coords = Transform::all().iter().map(|transform| {
+            let points = self.real_coords.map(|p| transform.apply_one(&p));
+            let min_rows = points.iter().map(|p| p.rows).min().unwrap();
+            let min_cols = points.iter().map(|p| p.cols).min().unwrap();
+            let mut points = points.map(|p| OffsetCoord::new(p.rows - min_rows, p.cols - min_cols));
+            points.sort();
+            points
+        }).min().unwrap();
+
+        Tetromino {
+            kind: self.kind,
+            anchor: Coord::new(0, 0),
+            points: coords,
+            real_coords: coords,
+            transform: Transform::Identity__,
+        }
+    }
+
     /// The canonical notation for the piece; must be in bounds!
     pub fn notate(&self) -> String {
         let arr = self.real_coords_lazy().map(|c| c.coerce().notate()).join(",");
@@ -205,7 +245,7 @@ impl Tetromino {
             [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 4, 4, 4, 4, 9, 9] => Tile::I,
             [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4] => Tile::T,
             [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 5, 5] => Tile::S,
-            _                                                => { return Err(anyhow!("coords {coords:?} are not a valid tetromino!")); }
+            _                                                => { return Err(anyhow!("coords {} are not a valid tetromino!", coords.iter().join(","))); }
         };
         if real_kind != kind {
             return Err(anyhow!("given Tile {kind:?}, but this Tetromino is of type {real_kind:?}"));
@@ -220,3 +260,55 @@ impl Tetromino {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_is_invariant_under_symmetry() {
+        for kind in Tile::all() {
+            let piece = Tetromino::identity(kind, &Coord::new(4, 4));
+            let expected = piece.normalized();
+            for transform in Transform::all() {
+                let copy = transform.apply(&piece).at(&Coord::new(2, 7));
+                assert_eq!(copy.normalized(), expected, "{kind:?} under {transform:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn cells_by_row_and_col() {
+        use crate::battle_of_lits::testing::*;
+
+        let vertical = piecemap().get_piece(piece("I[06,16,26,36]"));
+        for row in 0..4 {
+            assert_eq!(vertical.cells_in_row(row), vec![6]);
+        }
+        assert!(vertical.cells_in_row(4).is_empty());
+        assert_eq!(vertical.cells_in_col(6), vec![0, 1, 2, 3]);
+        assert!(vertical.cells_in_col(5).is_empty());
+
+        let l = piecemap().get_piece(piece("L[65,75,85,86]"));
+        assert_eq!(l.cells_in_row(8), vec![5, 6]);
+    }
+
+    #[test]
+    fn from_anchor_transform_matches_the_piecemap() {
+        let piecemap = crate::battle_of_lits::testing::piecemap();
+        for id in 0..NUM_PIECES {
+            let piece = piecemap.get_piece(id);
+            let rebuilt = Tetromino::from_anchor_transform(piece.kind, &piecemap.anchor_of(id), piecemap.transform_of(id));
+            assert!(rebuilt.in_bounds());
+            assert_eq!(rebuilt, piece);
+            assert_eq!(piecemap.try_and_find(&rebuilt.real_coords()).unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn normalized_distinguishes_kinds() {
+        let anchor = Coord::new(4, 4);
+        let shapes = Tile::all().map(|kind| Tetromino::identity(kind, &anchor).normalized().real_coords);
+        assert!(shapes.iter().all_unique());
+    }
+}
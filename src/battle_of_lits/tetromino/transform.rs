@@ -80,6 +80,16 @@ impl Transform {
         }
     }
 
+    /// Applies a transformation to a board coordinate about the centre of the board.
+    /// 
+    /// The board centre sits between cells, so we work in doubled coordinates centred on it.
+    pub fn apply_board(&self, coord: &Coord) -> Coord {
+        let extent = BOARD_SIZE as isize - 1;
+        let centred = OffsetCoord::new(2 * coord.row as isize - extent, 2 * coord.col as isize - extent);
+        let OffsetCoord { rows, cols } = self.apply_one(&centred);
+        Coord::new(((rows + extent) / 2) as usize, ((cols + extent) / 2) as usize)
+    }
+
     /// Returns the canonical (most direct) transform for this transform and the given tile type.
     pub fn canonicalize(&self, lits: &Tile) -> Transform {
         match lits {
@@ -1,5 +1,4 @@
 #![allow(dead_code)]
-#![feature(never_type)]
 #![feature(impl_trait_in_assoc_type)]
 #![feature(iter_collect_into)]
 
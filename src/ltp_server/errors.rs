@@ -0,0 +1,56 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use crate::prelude::*;
+
+/// A machine-readable category for a failed command, attached to its error as context and printed after `err`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The command needs a game, but none is loaded.
+    NoGame,
+
+    /// The move (or loaded game) is not legal in the position.
+    IllegalMove,
+
+    /// The command's arguments could not be understood.
+    ParseError,
+
+    /// The command itself is not recognized.
+    UnknownCommand,
+
+    /// Anything else, such as a failure in the engine; errors without a code fall back to this.
+    Internal,
+}
+
+impl ErrorCode {
+    /// Gets the code attached to an error, if any.
+    pub fn of(err: &Error) -> ErrorCode {
+        err.downcast_ref::<ErrorCode>().copied().unwrap_or(ErrorCode::Internal)
+    }
+
+    /// Gets the human-readable part of an error, without the code itself.
+    pub fn message(err: &Error) -> String {
+        match err.downcast_ref::<ErrorCode>() {
+            Some(_) => err.chain().skip(1).join(": "),
+            None    => format!("{err:#}"),
+        }
+    }
+
+    /// Gets the token this code is printed as.
+    pub fn notate(&self) -> &'static str {
+        match self {
+            ErrorCode::NoGame         => "nogame",
+            ErrorCode::IllegalMove    => "illegalmove",
+            ErrorCode::ParseError     => "parseerror",
+            ErrorCode::UnknownCommand => "unknowncommand",
+            ErrorCode::Internal       => "internal",
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.notate())
+    }
+}
@@ -1,70 +1,132 @@
+mod errors;
 mod options;
 
-use std::{process::exit, time::Duration};
+use std::{io::{BufRead, ErrorKind}, time::Duration};
 
 use itertools::Itertools;
+pub use errors::ErrorCode;
 pub use options::LTPServerOptions;
 
 use crate::prelude::*;
 
-pub struct LTPServer {
-    agent: BLITSAgent,
-    board: Option<Board<'static>>,
-    past_boards: Vec<Board<'static>>,
-    piecemap: &'static PieceMap,
-    #[allow(dead_code)]
+/// The number of consecutive interrupted reads tolerated before the input stream is treated as broken.
+const MAX_READ_RETRIES: u32 = 5;
+
+pub struct LTPServer<'a> {
+    agent: BLITSAgent<'a>,
+    board: Option<Board<'a>>,
+    past_boards: Vec<Board<'a>>,
+    piecemap: &'a PieceMap,
     config: LTPServerOptions,
     dirty: bool,
+    replay: Vec<usize>,
+    openings: Option<SelfplayOpenings>,
+    last_recorded: Option<String>,
+    quitting: bool,
 }
 
-impl LTPServer {
-    /// Produces a new LTP server with the given BLITS engine configuration.
-    pub fn new(options: LTPServerOptions, piecemap: &'static PieceMap) -> LTPServer {
-        LTPServer {
+impl<'a> LTPServer<'a> {
+    /// Produces a new LTP server with the given BLITS engine configuration, failing if `--selfplay-openings` can't be
+    /// loaded.
+    /// 
+    /// Under `--no-leak-piecemap`, pondering is turned off, so that no background search can outlive the server and
+    /// still hold boards borrowing the piecemap when it is freed.
+    pub fn new(mut options: LTPServerOptions, piecemap: &'a PieceMap) -> Result<LTPServer<'a>> {
+        if options.no_leak_piecemap {
+            options.ponder = false;
+        }
+
+        let openings = options.selfplay_openings.as_ref()
+            .map(|path| {
+                SelfplayOpenings::load(path, piecemap, options.selfplay_seed)
+                    .with_context(|| format!("could not load --selfplay-openings from {}", path.display()))
+            })
+            .transpose()?;
+
+        Ok(LTPServer {
             agent: options.agent_config().get_agent(piecemap),
             board: None,
             past_boards: vec![],
             piecemap,
             config: options,
-            dirty: true
-        }
+            dirty: true,
+            replay: vec![],
+            openings,
+            last_recorded: None,
+            quitting: false,
+        })
     }
 
-    /// Runs BLITS in engine mode.
-    pub fn run(&mut self) -> Result<!> {
+    /// Runs BLITS in engine mode, returning on `quit` or at the end of input.
+    pub fn run(&mut self) -> Result<()> {
         let a_bit = std::time::Duration::from_secs(2);
         std::thread::sleep(a_bit);
 
+        self.warmup()?;
+        self.serve(&mut std::io::stdin().lock())
+    }
+
+    /// Applies commands from the reader until `quit` or the end of input.
+    fn serve(&mut self, reader: &mut impl BufRead) -> Result<()> {
+        self.quitting = false;
         loop
         {
-            let mut cmdstr: String = String::new();
-            std::io::stdin().read_line(&mut cmdstr)?;
+            let Some(cmdstr) = read_command(reader)? else {
+                log::info!("reached end of input; exiting");
+                return Ok(());
+            };
 
             let args: Vec<&str> = cmdstr.split_whitespace().filter(|s| !s.is_empty()).collect();
             let cmd = *args.first().unwrap_or(&"");
 
             self.apply(cmd, &args[1..])?;
+            if self.quitting {
+                return Ok(());
+            }
         }
     }
 
+    /// Searches a freshly generated board with the serving agent for the `--warmup` duration, so that the first real
+    /// search doesn't pay cold-cache costs; the agent's game is left as it was. Returns whether a search ran.
+    fn warmup(&mut self) -> Result<bool> {
+        let duration = Duration::from_millis(self.config.warmup.unwrap_or(0));
+        if duration.is_zero() {
+            return Ok(false);
+        }
+
+        let start = std::time::Instant::now();
+        let board = Board::new(None, self.piecemap).with_options(self.config.board_options());
+        self.agent.warm_up(&board, duration)?;
+
+        log::info!("warmed up in {:.2}s", start.elapsed().as_secs_f64());
+        Ok(true)
+    }
+
     /// Runs a command.
     fn apply(&mut self, cmd: &str, args: &[&str]) -> Result<()> {
         let result = match cmd
         {
             | "" => Ok(()),
+            | "autoswap" => self.auto_swap(args),
+            | "back" => self.back(args),
             | "bestmove" => self.best_move(args),
-            | "info" => self.info(),
+            | "cleartt" => self.clear_tt(args),
+            | "export" => self.export(args),
+            | "info" => self.info(args),
             | "newgame" => self.new_game(args),
             | "options" => self.options(args),
             | "play" => self.play_move(args),
             | "pv" => self.principal_variation(args),
             | "print" => self.print(args),
-            | "quit" => exit(0),
+            | "quit" => self.quit(args),
+            | "replay" => self.start_replay(args),
             | "score" => self.score(args),
-            | "swap" => self.play_move(&["swap"]),
+            | "step" => self.step(args),
+            | "summary" => self.summary(args),
+            | "swap" => self.swap(args),
             | "undo" => self.undo_move(args),
             | "validmoves" => self.valid_moves(args),
-            | _ => Err(anyhow!("unrecognized command {cmd}")),
+            | _ => Err(anyhow!("unrecognized command {cmd}").context(ErrorCode::UnknownCommand)),
         };
 
         match result
@@ -74,26 +136,45 @@ impl LTPServer {
                 self.ok()
             },
             Err(err) => {
-                log::warn!("encountered recoverable error:\n{err}");
+                log::warn!("encountered recoverable error:\n{err:#}");
                 self.err(&err)
             },
         }
     }
 
+    /// Swaps if the engine judges it beneficial, otherwise leaves the position unchanged.
+    fn auto_swap(&mut self, _args: &[&str]) -> Result<()> {
+        self.ensure_started()?;
+
+        let board = self.get().clone();
+        if self.agent.auto_swap_decision() {
+            self.past_boards.push(board);
+            self.get_mut().pass()?;
+            self.dirty = true;
+        }
+
+        println!("{}", self.get().notate());
+        Ok(())
+    }
+
     fn best_move(&mut self, args: &[&str]) -> Result<()> {
         self.ensure_started()?;
 
         if args.len() >= 2 {
             match args[0] {
                 "depth" => {
-                    let depth = args[1].parse::<u8>()?;
+                    let depth = self.search_depth(args[1]).context(ErrorCode::ParseError)?;
                     self.agent.set_max_depth(depth);
                 },
                 "time"  => {
-                    let time = self.parse_hhmmss(args[1])?;
+                    let time = self.search_budget(args[1]).context(ErrorCode::ParseError)?;
+                    self.agent.set_max_time(time);
+                },
+                "clock" => {
+                    let time = self.clock_budget(args[1]).context(ErrorCode::ParseError)?;
                     self.agent.set_max_time(time);
                 },
-                _       => { return Err(anyhow!("unrecognized search option {}", args[0])); }
+                _       => { return Err(anyhow!("unrecognized search option {}", args[0]).context(ErrorCode::ParseError)); }
             };
         }
         let mv = self.agent.generate_move()?;
@@ -103,18 +184,61 @@ impl LTPServer {
         Ok(())
     }
 
+    /// Clears the engine's transposition table, e.g. between unrelated positions in a batch.
+    fn clear_tt(&mut self, _args: &[&str]) -> Result<()> {
+        self.agent.clear_tt();
+        Ok(())
+    }
+
+    /// Prints the gamestring for the current position, optionally with the compressed setup.
+    fn export(&mut self, args: &[&str]) -> Result<()> {
+        self.ensure_started()?;
+
+        let gamestr = match args.first() {
+            None               => self.get().notate(),
+            Some(&"compressed") => self.get().notate_compressed(),
+            Some(other)        => { return Err(anyhow!("unrecognized export format {other}").context(ErrorCode::ParseError)); }
+        };
+        println!("{}", gamestr);
+        Ok(())
+    }
+
     /// Starts a new game, potentially from an advanced position (i.e. with a move history).
+    /// 
+    /// Without a gamestring, the game starts from the next `--selfplay-openings` opening if there are any.
     fn new_game(&mut self, args: &[&str]) -> Result<()> {
+        let gamestr = match (self.parse_gamestring(args)?, &mut self.openings) {
+            (None, Some(openings)) => Some(openings.next_opening()),
+            (gamestr, _)           => gamestr,
+        };
+        self.agent.clear_tt();
+        self.load(gamestr)?;
+        self.replay = vec![];
+
+        println!("{}", self.get().notate());
+        Ok(())
+    }
+
+    /// Parses a gamestring from the arguments (if any), checking it when running under `--strict`.
+    fn parse_gamestring(&self, args: &[&str]) -> Result<Option<GameString>> {
         let gamestr = if !args.is_empty() {
-            Some(args.join(" ").parse::<GameString>()?)
+            Some(args.join(" ").parse::<GameString>().context(ErrorCode::ParseError)?)
         } else {
             None
         };
 
+        if self.config.strict && let Some(s) = &gamestr {
+            self.check_strict(s).context(ErrorCode::IllegalMove)?;
+        }
+        Ok(gamestr)
+    }
+
+    /// Loads a game into both the server and the agent, playing each of its moves in turn.
+    fn load(&mut self, gamestr: Option<GameString>) -> Result<()> {
         match gamestr {
             Some(s) => {
                 let GameString { setup, moves } = s; {
-                    self.board = Some(Board::new(Some(setup.grid), self.piecemap));
+                    self.board = Some(Board::new(Some(setup.grid), self.piecemap).with_options(self.config.board_options()));
                     self.agent.new(Some(setup));
                 }
 
@@ -124,59 +248,157 @@ impl LTPServer {
                     let MoveString { repr: _, tetromino } = mv;
                     match tetromino {
                         Some(t) => {
-                            let index = self.piecemap.try_and_find(&t.real_coords())?;
-                            self.get_mut().play(index)?;
+                            let index = self.piecemap.try_and_find(&t.real_coords()).context(ErrorCode::IllegalMove)?;
+                            self.get_mut().play(index).context(ErrorCode::IllegalMove)?;
                             self.agent.play_move(index)?;
                         },
                         None => {
-                            self.get_mut().pass()?;
+                            self.get_mut().pass().context(ErrorCode::IllegalMove)?;
                             self.agent.play_move(NULL_MOVE)?;
                         }
                     }
                 }
             },
             None => {
-                self.board = Some(Board::new(None, self.piecemap));
+                self.board = Some(Board::new(None, self.piecemap).with_options(self.config.board_options()));
                 self.agent.with_board(&self.get().clone());
             }
         };
         self.dirty = true;
+        Ok(())
+    }
+
+    /// Loads a game for stepping through with `step` and `back`, starting from its setup.
+    /// 
+    /// The whole game is played and then undone, so the agent keeps the remaining plies as its redo history.
+    fn start_replay(&mut self, args: &[&str]) -> Result<()> {
+        if args.is_empty() {
+            return Err(anyhow!("no game to replay").context(ErrorCode::ParseError));
+        }
+        let gamestr = self.parse_gamestring(args)?;
+        self.load(gamestr)?;
+        self.replay = self.get().plies();
+
+        while let Some(board) = self.past_boards.pop() {
+            self.agent.undo_move()?;
+            self.board = Some(board);
+        }
+
+        println!("{}", self.get().notate());
+        Ok(())
+    }
+
+    /// Advances the replay by one ply.
+    fn step(&mut self, _args: &[&str]) -> Result<()> {
+        self.ensure_started()?;
+
+        let ply = self.get().move_count();
+        if self.replay.is_empty() || !self.get().plies().iter().eq(&self.replay[..ply.min(self.replay.len())]) {
+            return Err(anyhow!("the current position is not part of a replay").context(ErrorCode::IllegalMove));
+        }
+        let Some(&mv) = self.replay.get(ply) else {
+            return Err(anyhow!("reached the end of the replay").context(ErrorCode::IllegalMove));
+        };
+
+        let board = self.get().clone();
+        match mv {
+            NULL_MOVE => self.get_mut().pass(),
+            _         => self.get_mut().play(mv),
+        }.context(ErrorCode::IllegalMove)?;
+        self.agent.play_move(mv)?;
+        self.past_boards.push(board);
+        self.dirty = true;
 
         println!("{}", self.get().notate());
         Ok(())
     }
 
-    fn options(&mut self, _args: &[&str]) -> Result<()> {
+    /// Rewinds the replay by one ply.
+    fn back(&mut self, args: &[&str]) -> Result<()> {
+        self.ensure_started()?;
+        if self.replay.is_empty() {
+            return Err(anyhow!("no replay in progress").context(ErrorCode::IllegalMove));
+        }
+        self.undo_move(args)
+    }
+
+    /// Replays a gamestring on a scratch board and rejects it if the resulting position is illegal.
+    fn check_strict(&self, gamestr: &GameString) -> Result<()> {
+        Board::from_gamestring(gamestr, self.piecemap)?
+            .validate_position().context("illegal position rejected under --strict")
+    }
+
+    /// Gets or sets engine options; currently only `ponder <on|off>`, which rebuilds the agent's strategy.
+    fn options(&mut self, args: &[&str]) -> Result<()> {
+        match args {
+            [] => {},
+            ["ponder", "on"] if self.config.no_leak_piecemap => {
+                return Err(anyhow!("pondering is disabled under --no-leak-piecemap").context(ErrorCode::ParseError));
+            },
+            ["ponder", value] => {
+                self.config.ponder = match *value {
+                    "on"  => true,
+                    "off" => false,
+                    _     => return Err(anyhow!("expected on or off, received {value}").context(ErrorCode::ParseError)),
+                };
+                self.agent.reconfigure(&self.config.agent_config());
+            },
+            _ => return Err(anyhow!("unrecognized option {}", args.join(" ")).context(ErrorCode::ParseError)),
+        };
+
+        println!("ponder {}", if self.config.ponder { "on" } else { "off" });
         Ok(())
     }
 
+    /// Plays a move; `play swap` is equivalent to `swap`.
     fn play_move(&mut self, args: &[&str]) -> Result<()> {
         self.ensure_started()?;
 
         if args.is_empty() {
-            return Err(anyhow!("no move provided"));
+            return Err(anyhow!("no move provided").context(ErrorCode::ParseError));
         }
 
-        self.past_boards.push(self.get().clone());
-
-        let MoveString { repr: _, tetromino } = args[0].parse::<MoveString>()?;
-        match tetromino {
-            Some(t) => {
-                let index = self.piecemap.try_and_find(&t.real_coords())?;
-                self.get_mut().play(index)?;
-                self.agent.play_move(index)?;
-            },
-            None    => {
-                self.get_mut().pass()?;
-                self.agent.play_move(NULL_MOVE)?;
-            }
+        let MoveString { repr: _, tetromino } = args[0].parse::<MoveString>().context(ErrorCode::ParseError)?;
+        let Some(t) = tetromino else {
+            return self.swap(&args[1..]);
         };
+
+        let index = self.piecemap.try_and_find(&t.real_coords()).context(ErrorCode::IllegalMove)?;
+        let board = self.get().clone();
+        let outcome = self.get_mut().play_verbose(index).context(ErrorCode::IllegalMove)?;
+        log::debug!("played {}: score {} -> {}, protected {}", t.notate(), outcome.score_before, outcome.score_after, outcome.newly_protected.len());
+        self.agent.play_move(index)?;
+        self.past_boards.push(board);
         self.dirty = true;
+        self.record_to_book();
 
         println!("{}", self.get().notate());
         Ok(())
     }
 
+    /// Records the game into the `--book-write` book, if there is one and the game just finished. The move is already
+    /// played by now, so a failure to write the book is only logged; a game that was already recorded (e.g. because its
+    /// last move was undone and replayed) is skipped.
+    fn record_to_book(&mut self) {
+        let Some(path) = &self.config.book_write else {
+            return;
+        };
+        let game = self.get().notate();
+        if !self.get().is_terminal() || self.last_recorded.as_ref() == Some(&game) {
+            return;
+        }
+
+        match OpeningBook::record_to_file(path, self.get()) {
+            Ok(recorded) => {
+                if recorded {
+                    log::info!("recorded the finished game into {}", path.display());
+                }
+                self.last_recorded = Some(game);
+            },
+            Err(e) => log::warn!("failed to record the finished game into {}: {e:#}", path.display()),
+        };
+    }
+
     fn principal_variation(&mut self, _args: &[&str]) -> Result<()> {
         self.ensure_started()?;
 
@@ -193,15 +415,55 @@ impl LTPServer {
     fn print(&mut self, _args: &[&str]) -> Result<()> {
         self.ensure_started()?;
 
-        println!("{}", self.get().pretty());
+        let board = self.get();
+        println!("{}", if self.config.ascii_only { board.pretty_plain() } else { board.pretty() });
+        Ok(())
+    }
+
+    /// Prints the material score, or a specific positional term from the evaluation.
+    /// Stops serving once the current command is done, so the server can be dropped cleanly.
+    fn quit(&mut self, _args: &[&str]) -> Result<()> {
+        self.quitting = true;
+        Ok(())
+    }
+
+    fn score(&mut self, args: &[&str]) -> Result<()> {
+        self.ensure_started()?;
+
+        match args.first() {
+            None           => println!("{}", self.get().score()),
+            Some(&"tempo") => println!("tempo {}", self.get().tempo_advantage()),
+            Some(other)    => { return Err(anyhow!("unrecognized score query {other}").context(ErrorCode::ParseError)); }
+        };
+        Ok(())
+    }
+
+    /// Prints everything known about the latest search in this game, or `none` if there hasn't been one.
+    fn summary(&mut self, _args: &[&str]) -> Result<()> {
+        let Some(SearchSummary { info, elapsed }) = self.agent.last_search_summary() else {
+            println!("none");
+            return Ok(());
+        };
+
+        let pv = info.pv.iter().map(|mv| self.piecemap.notate(*mv)).join("; ");
+        println!(
+            "depth {} time {} bestmove {} score {} pv {pv}",
+            info.depth, elapsed.as_millis(), self.piecemap.notate(info.best_move), info.score
+        );
         Ok(())
     }
 
-    fn score(&mut self, _args: &[&str]) -> Result<()> {
+    /// Swaps X and O, if legal; this is the single path for both `swap` and `play swap`.
+    fn swap(&mut self, _args: &[&str]) -> Result<()> {
         self.ensure_started()?;
 
-        let score = self.get().score();
-        println!("{}", score);
+        let board = self.get().clone();
+        self.get_mut().pass().context(ErrorCode::IllegalMove)?;
+        self.agent.swap()?;
+        self.past_boards.push(board);
+        self.dirty = true;
+
+        println!("{}", self.get().notate());
         Ok(())
     }
 
@@ -228,44 +490,56 @@ impl LTPServer {
 
     // accessors
 
-    fn ensure_started(&mut self) -> Result<&mut Board<'static>> {
+    fn ensure_started(&mut self) -> Result<&mut Board<'a>> {
         if self.board.is_none() {
-            Err(anyhow!("no game in progress"))
+            Err(anyhow!("no game in progress").context(ErrorCode::NoGame))
         } else {
             Ok(self.get_mut())
         }
     }
 
     /// Retrieves the board in a shared context.
-    fn get(&self) -> & Board<'static> {
+    fn get(&self) -> & Board<'a> {
         self.board.as_ref().unwrap()
     } 
 
     /// Retrieves the board in a mutable context.
-    fn get_mut(&mut self) -> & mut Board<'static> {
+    fn get_mut(&mut self) -> & mut Board<'a> {
         self.board.as_mut().unwrap()
     }
 
     // basic printers
 
-    /// Prints the server's ID.
-    fn info(&self) -> Result<()>
+    /// Prints the server's ID, or a specific piece of information about the current game.
+    fn info(&mut self, args: &[&str]) -> Result<()>
     {
-        println!(
-            "id {} v{}",
-            env!("CARGO_PKG_NAME"),
-            env!("CARGO_PKG_VERSION")
-        );
+        match args.first() {
+            None => println!(
+                "id {} v{}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ),
+            Some(&"phase") => {
+                self.ensure_started()?;
+                println!("phase {}", self.get().phase().notate());
+            },
+            Some(other) => { return Err(anyhow!("unrecognized info query {other}").context(ErrorCode::ParseError)); }
+        };
         Ok(())
     }
 
-    /// Prints an error to the UHP stream.
+    /// Prints an error to the UHP stream, as `err <code> <message>`.
     fn err(&self, err: &Error) -> Result<()>
     {
-        println!("err\n{}", err);
+        println!("{}", self.format_err(err));
         self.ok()
     }
 
+    /// Formats an error line; errors without an attached `ErrorCode` are reported as internal.
+    fn format_err(&self, err: &Error) -> String {
+        format!("err {} {}", ErrorCode::of(err), ErrorCode::message(err))
+    }
+
     /// Prints the ok footer to the UHP stream.
     fn ok(&self) -> Result<()>
     {
@@ -273,6 +547,33 @@ impl LTPServer {
         Ok(())
     }
 
+    /// The depth the engine may actually search to, given a requested depth; requests beyond `--max-depth` are clamped.
+    fn search_depth(&self, depth: &str) -> Result<u8> {
+        let requested = depth.parse::<u8>()?;
+        match self.config.max_depth {
+            Some(cap) if requested > cap => {
+                log::warn!("requested depth {requested} exceeds --max-depth; searching to depth {cap}");
+                Ok(cap)
+            },
+            _ => Ok(requested),
+        }
+    }
+
+    /// The time the engine may actually search for, given a requested duration; the configured move overhead is held
+    /// back so that the move reaches the controller before its deadline.
+    fn search_budget(&self, time: &str) -> Result<Duration> {
+        let requested = self.parse_hhmmss(time)?;
+        Ok(requested.saturating_sub(Duration::from_millis(self.config.move_overhead)))
+    }
+
+    /// The time the engine may search for out of its remaining game clock: an even share across the moves it still
+    /// expects to make (half of `Board::plies_remaining_estimate`), less the move overhead.
+    fn clock_budget(&self, clock: &str) -> Result<Duration> {
+        let remaining = self.parse_hhmmss(clock)?;
+        let own_moves = self.get().plies_remaining_estimate().div_ceil(2).max(1);
+        Ok((remaining / own_moves as u32).saturating_sub(Duration::from_millis(self.config.move_overhead)))
+    }
+
     // parsers
 
     fn parse_hhmmss(&self, time: &str) -> Result<Duration> {
@@ -283,3 +584,336 @@ impl LTPServer {
         Ok(Duration::from_secs(hours * 3600 + minutes * 60 + seconds))
     }
 }
+
+/// Reads a single command line, retrying reads that were interrupted by a signal with a short linear backoff.
+/// 
+/// Returns `None` once the input reaches EOF; any other error is fatal.
+fn read_command(reader: &mut impl BufRead) -> Result<Option<String>> {
+    let mut retries = 0;
+    loop {
+        let mut cmdstr = String::new();
+        match reader.read_line(&mut cmdstr) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(cmdstr)),
+            Err(e) if e.kind() == ErrorKind::Interrupted && retries < MAX_READ_RETRIES => {
+                retries += 1;
+                log::debug!("read interrupted, retrying ({retries}/{MAX_READ_RETRIES})");
+                std::thread::sleep(Duration::from_millis(10 * retries as u64));
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor, ErrorKind, Read};
+    use std::time::Duration;
+
+    use clap::Parser;
+
+    use super::{read_command, ErrorCode, LTPServer, LTPServerOptions};
+    use crate::prelude::{anyhow, BookMove, GameString, OpeningBook, PieceMap, Player, WhichStrategy};
+    use crate::battle_of_lits::testing::*;
+
+    fn server() -> LTPServer<'static> {
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1"]);
+        LTPServer::new(options, piecemap()).unwrap()
+    }
+
+    fn strict_server() -> LTPServer<'static> {
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--strict"]);
+        LTPServer::new(options, piecemap()).unwrap()
+    }
+
+    /// A reader that fails with `Interrupted` a fixed number of times before yielding its data.
+    struct FlakyReader {
+        interruptions: usize,
+        data: Cursor<&'static [u8]>,
+    }
+
+    impl Read for FlakyReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.data.read(buf)
+        }
+    }
+
+    impl BufRead for FlakyReader {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            self.data.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.data.consume(amt)
+        }
+
+        fn read_line(&mut self, buf: &mut String) -> std::io::Result<usize> {
+            if self.interruptions > 0 {
+                self.interruptions -= 1;
+                return Err(ErrorKind::Interrupted.into());
+            }
+            self.data.read_line(buf)
+        }
+    }
+
+    #[test]
+    fn retries_interrupted_reads() {
+        let mut reader = FlakyReader { interruptions: 2, data: Cursor::new(b"info\n") };
+        assert_eq!(read_command(&mut reader).unwrap().as_deref(), Some("info\n"));
+        assert_eq!(read_command(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn gives_up_on_persistent_interruptions() {
+        let mut reader = FlakyReader { interruptions: usize::MAX, data: Cursor::new(b"info\n") };
+        assert!(read_command(&mut reader).is_err());
+    }
+
+    #[test]
+    fn swap_spellings_are_equivalent() {
+        let [mut bare, mut play] = [server(), server()];
+        for server in [&mut bare, &mut play] {
+            server.new_game(&[&format!("{SETUP};"), "I[96,97,98,99]"]).unwrap();
+        }
+
+        bare.swap(&[]).unwrap();
+        play.play_move(&["swap"]).unwrap();
+        assert_eq!(bare.get().notate(), play.get().notate());
+
+        let errors = [bare.swap(&[]).unwrap_err(), play.play_move(&["swap"]).unwrap_err()];
+        assert_eq!(errors[0].to_string(), errors[1].to_string());
+        assert_eq!(bare.past_boards.len(), play.past_boards.len());
+    }
+
+    #[test]
+    fn replay_steps_through_the_game() {
+        let mut server = server();
+        let gamestr = format!("{SETUP}; {FINISHED}");
+        server.start_replay(&[&gamestr]).unwrap();
+        assert_eq!(server.get().notate(), board(SETUP).notate());
+        assert!(server.back(&[]).is_err());
+
+        for _ in 0..15 {
+            server.step(&[]).unwrap();
+        }
+        assert_eq!(server.get().notate(), board(&gamestr).notate());
+        assert!(server.step(&[]).is_err());
+
+        for _ in 0..3 {
+            server.back(&[]).unwrap();
+        }
+        assert_eq!(server.get().move_count(), 12);
+        server.best_move(&["depth", "1"]).unwrap();
+        server.step(&[]).unwrap();
+        assert_eq!(server.get().move_count(), 13);
+
+        let swapped = format!("{SETUP}; I[96,97,98,99]; swap; L[65,75,85,86]");
+        server.start_replay(&[&swapped]).unwrap();
+        for _ in 0..3 {
+            server.step(&[]).unwrap();
+        }
+        assert_eq!(server.get().notate(), board(&swapped).notate());
+
+        server.new_game(&[SETUP]).unwrap();
+        assert_eq!(ErrorCode::of(&server.step(&[]).unwrap_err()), ErrorCode::IllegalMove);
+        assert_eq!(ErrorCode::of(&server.back(&[]).unwrap_err()), ErrorCode::IllegalMove);
+    }
+
+    #[test]
+    fn errors_carry_their_code() {
+        let mut server = server();
+        let err = server.play_move(&["I[96,97,98,99]"]).unwrap_err();
+        assert_eq!(ErrorCode::of(&err), ErrorCode::NoGame);
+
+        server.new_game(&[&format!("{SETUP};"), "I[96,97,98,99]"]).unwrap();
+        let err = server.play_move(&["I[95,96,97,98]"]).unwrap_err();
+        assert_eq!(ErrorCode::of(&err), ErrorCode::IllegalMove);
+        assert!(server.format_err(&err).starts_with("err illegalmove "));
+
+        assert_eq!(ErrorCode::of(&server.play_move(&["Q[00]"]).unwrap_err()), ErrorCode::ParseError);
+        assert_eq!(ErrorCode::of(&anyhow!("boom")), ErrorCode::Internal);
+    }
+
+    #[test]
+    fn strategy_selects_the_random_agent() {
+        let options = LTPServerOptions::parse_from(["blits", "--mcts", "--strategy", "random", "--seed", "3"]);
+        let config = options.agent_config();
+        assert_eq!(config.selected, WhichStrategy::Random);
+        assert_eq!(config.random_seed, 3);
+    }
+
+    #[test]
+    fn clearing_the_table_keeps_analyses_consistent() {
+        let mut server = server();
+        server.new_game(&[&format!("{SETUP};"), "I[96,97,98,99]; L[65,75,85,86]"]).unwrap();
+
+        let analyse = |server: &mut LTPServer| {
+            server.agent.set_max_depth(2);
+            server.agent.generate_move().unwrap()
+        };
+        let before = analyse(&mut server);
+        server.apply("cleartt", &[]).unwrap();
+        assert_eq!(analyse(&mut server), before);
+    }
+
+    #[test]
+    fn finished_games_are_written_to_the_book() {
+        let path = std::env::temp_dir().join(format!("blits-server-book-{}.txt", std::process::id()));
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--book-write", path.to_str().unwrap()]);
+        let mut server = LTPServer::new(options, piecemap()).unwrap();
+
+        server.new_game(&[SETUP]).unwrap();
+        for mv in FINISHED.split("; ") {
+            assert!(!path.exists());
+            server.play_move(&[mv]).unwrap();
+        }
+        server.undo_move(&[]).unwrap();
+        server.play_move(&[FINISHED.rsplit("; ").next().unwrap()]).unwrap(); // the same game, so it isn't recorded again
+
+        let book = OpeningBook::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(book.moves(&board(SETUP)), [BookMove { id: piece("L[27,26,25,35]"), games: 1, score: 1 }]);
+    }
+
+    #[test]
+    fn book_failures_do_not_fail_the_move() {
+        let dir = std::env::temp_dir(); // a directory, so the book can't be read or written
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--book-write", dir.to_str().unwrap()]);
+        let mut server = LTPServer::new(options, piecemap()).unwrap();
+
+        server.new_game(&[SETUP]).unwrap();
+        for mv in FINISHED.split("; ") {
+            server.play_move(&[mv]).unwrap();
+        }
+        assert!(server.get().is_terminal());
+    }
+
+    #[test]
+    fn bare_newgames_cycle_through_the_openings() {
+        let first = format!("{SETUP}; I[38,37,36,35]");
+        let second = format!("{SETUP}; I[38,37,36,35]; L[53,54,55,45]; L[14,15,16,26]");
+        let path = std::env::temp_dir().join(format!("blits-server-openings-{}.txt", std::process::id()));
+        std::fs::write(&path, format!("{first}\n{second}\n")).unwrap();
+
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--selfplay-openings", path.to_str().unwrap()]);
+        let mut server = LTPServer::new(options.clone(), piecemap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(LTPServer::new(options, piecemap()).is_err()); // a missing openings file fails startup
+
+        let mut started = vec![];
+        for _ in 0..3 {
+            server.new_game(&[]).unwrap();
+            started.push(server.get().move_count());
+        }
+        assert_eq!(started, vec![1, 3, 1]);
+
+        server.new_game(&[SETUP]).unwrap(); // an explicit gamestring still wins
+        assert_eq!(server.get().move_count(), 0);
+    }
+
+    #[test]
+    fn strict_rejects_illegal_positions() {
+        let mut server = strict_server();
+        server.new_game(&[&format!("{SETUP};"), "I[96,97,98,99]"]).unwrap();
+
+        let mut imported: GameString = SETUP.parse().unwrap(); // a snapshot grid that never went through the parser
+        imported.setup.grid.0[0][5] = imported.setup.grid.0[0][5].with_cell(Some(Player::X));
+
+        let err = server.check_strict(&imported).unwrap_err();
+        assert!(format!("{err:#}").contains("do not match"));
+        assert!(server.check_strict(&SETUP.parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn warmup_runs_only_when_asked() {
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--warmup", "0"]);
+        assert!(!LTPServer::new(options, piecemap()).unwrap().warmup().unwrap());
+        assert!(!server().warmup().unwrap());
+
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--warmup", "20"]);
+        let mut warm = LTPServer::new(options, piecemap()).unwrap();
+        assert!(warm.warmup().unwrap());
+        assert!(warm.board.is_none()); // the warmup doesn't leak into the served game
+
+        let options = LTPServerOptions::parse_from(["blits", "--warmup"]);
+        assert_eq!(options.warmup, Some(250));
+    }
+
+    #[test]
+    fn max_depth_clamps_requested_depths() {
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--max-depth", "2"]);
+        let mut capped = LTPServer::new(options, piecemap()).unwrap();
+        assert_eq!(capped.search_depth("1").unwrap(), 1);
+        assert_eq!(capped.search_depth("200").unwrap(), 2);
+        assert!(capped.search_depth("deep").is_err());
+
+        capped.new_game(&[&format!("{SETUP};"), "I[96,97,98,99]"]).unwrap();
+        capped.best_move(&["depth", "200"]).unwrap();
+
+        assert_eq!(server().search_depth("200").unwrap(), 200);
+    }
+
+    #[test]
+    fn clock_budget_shares_the_clock_across_the_remaining_moves() {
+        let mut server = server();
+        server.new_game(&[SETUP]).unwrap();
+        assert_eq!(server.clock_budget("00:01:40").unwrap(), Duration::from_secs(10)); // 20 plies left, 10 of them ours
+
+        let moves = FINISHED.split("; ").take(14).collect::<Vec<_>>().join("; ");
+        server.new_game(&[&format!("{SETUP};"), &moves]).unwrap();
+        let estimate = server.get().plies_remaining_estimate();
+        assert_eq!(server.clock_budget("00:01:40").unwrap(), Duration::from_secs(100) / estimate.div_ceil(2) as u32);
+    }
+
+    #[test]
+    fn move_overhead_shortens_the_search_budget() {
+        let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--move-overhead", "250"]);
+        let overhead = LTPServer::new(options, piecemap()).unwrap();
+        assert_eq!(overhead.search_budget("00:00:02").unwrap(), Duration::from_millis(1750));
+        assert_eq!(overhead.search_budget("00:00:00").unwrap(), Duration::ZERO);
+
+        assert_eq!(server().search_budget("00:01:00").unwrap(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn serving_returns_on_quit_or_end_of_input() {
+        let mut server = server();
+        server.serve(&mut format!("newgame {SETUP}\nquit\nplay I[38,37,36,35]\n").as_bytes()).unwrap();
+        assert_eq!(server.get().move_count(), 0); // nothing after the quit is applied
+
+        server.serve(&mut "play I[38,37,36,35]\nundo\n".as_bytes()).unwrap(); // a new session serves past the old quit
+        assert_eq!(server.get().move_count(), 0);
+
+        server.apply("quit", &[]).unwrap();
+        assert!(server.quitting);
+    }
+
+    #[test]
+    fn serving_borrows_a_local_piecemap() {
+        let local = PieceMap::new();
+        {
+            let options = LTPServerOptions::parse_from(["blits", "--num-threads", "1", "--table-mb", "1", "--no-leak-piecemap"]);
+            let mut server = LTPServer::new(options, &local).unwrap();
+            assert!(!server.config.ponder);
+            assert!(server.options(&["ponder", "on"]).is_err());
+
+            server.serve(&mut format!("newgame {SETUP}\nplay I[38,37,36,35]\nbestmove depth 1\nquit\n").as_bytes()).unwrap();
+            assert_eq!(server.get().move_count(), 1);
+        }
+        drop(local); // the server and every board it made are gone, so the map can be freed
+    }
+
+    #[test]
+    fn toggling_ponder_keeps_the_engine_searching() {
+        let mut server = server();
+        server.new_game(&[&format!("{SETUP};"), "I[96,97,98,99]"]).unwrap();
+
+        server.options(&["ponder", "off"]).unwrap();
+        assert!(!server.config.ponder);
+        server.options(&["ponder", "on"]).unwrap();
+        assert!(server.config.ponder);
+        assert!(server.options(&["ponder", "maybe"]).is_err());
+
+        server.best_move(&["depth", "1"]).unwrap();
+    }
+}
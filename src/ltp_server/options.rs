@@ -3,34 +3,104 @@ use crate::prelude::*;
 
 #[derive(Clone, Debug, Parser)]
 pub struct LTPServerOptions {
+    /// Prints boards in plain ASCII rather than emoji.
+    #[arg(long, default_value_t = false)]
+    pub ascii_only: bool,
+
+    /// Records the opening plies of every game finished through `play` into the opening book at this path.
+    #[arg(long)]
+    pub book_write: Option<std::path::PathBuf>,
+
+    /// Searches only one opening per symmetry class of the setup.
+    #[arg(long, default_value_t = false)]
+    pub canonical_openings: bool,
+
+    /// Bounds the magnitude of the heuristic evaluation, which otherwise saturates at the edges of the `i16` range.
+    #[arg(long)]
+    pub eval_clamp: Option<i16>,
+
+    /// Evaluates with exact reachability once at most this many pieces remain (6 if no value is given).
+    #[arg(long, num_args = 0..=1, default_missing_value = "6")]
+    pub eval_endgame_exact: Option<usize>,
+
+    /// Plays the move with the best static evaluation, without searching.
+    #[arg(long, default_value_t = false)]
+    pub eval_only: bool,
+
     #[arg(short, long)]
     pub log_level: Option<String>,
 
     #[arg(short, long)]
     pub num_threads: Option<usize>,
 
+    /// Clamps the depth requested by any `bestmove depth` to at most this many plies.
+    #[arg(long)]
+    pub max_depth: Option<u8>,
+
     #[arg(short, long, default_value_t = false)]
     pub mcts: bool,
 
+    /// Milliseconds reserved from each `bestmove time` budget to absorb I/O latency.
+    #[arg(long, default_value_t = 0)]
+    pub move_overhead: u64,
+
+    /// Frees the piecemap when the server exits cleanly, rather than leaking it, so leak checkers stay quiet; this also
+    /// turns off pondering.
+    #[arg(long, default_value_t = false)]
+    pub no_leak_piecemap: bool,
+
     #[arg(short, long, default_value_t = true)]
     pub ponder: bool,
 
+    /// Keys the transposition table by the position on the board rather than by the moves that reached it.
+    #[arg(long, default_value_t = false)]
+    pub position_hash: bool,
+
     #[arg(short, long, default_value_t = false)]
     pub quiescence: bool,
 
+    /// Seeds the `random` strategy.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Starts each bare `newgame` from the next opening in this file (one gamestring per line) instead of a fresh board.
+    #[arg(long)]
+    pub selfplay_openings: Option<std::path::PathBuf>,
+
+    /// Draws `--selfplay-openings` at random with this seed, rather than round-robin.
+    #[arg(long)]
+    pub selfplay_seed: Option<u64>,
+
+    /// Selects the strategy by name, overriding `--mcts` and `--eval-only`; `random` is a baseline for testing.
+    #[arg(long, value_enum)]
+    pub strategy: Option<WhichStrategy>,
+
+    /// Rejects loaded games whose position fails `Board::validate_position`.
+    #[arg(long, default_value_t = false)]
+    pub strict: bool,
+
     #[arg(long)]
     pub table_mb: Option<usize>,
 
+    /// Evaluates the root moves in parallel under `--eval-only`.
+    #[arg(long, default_value_t = false)]
+    pub threads_movegen: bool,
+
     #[arg(short, long, default_value_t = false)]
     pub verbose: bool,
 
+    /// Warms up the engine with a throwaway search of this many milliseconds (250 if no value is given) before accepting
+    /// commands.
+    #[arg(long, num_args = 0..=1, default_missing_value = "250")]
+    pub warmup: Option<u64>,
+
     #[arg(short, long)]
     pub window: Option<usize>,
 }
 
 impl LTPServerOptions {
     pub fn agent_config(&self) -> AgentConfig {
-        let mut config = AgentConfig::default();
+        let mut config = AgentConfig { board_opts: self.board_options(), ..AgentConfig::default() };
 
         if let Some(num_threads) = self.num_threads {
             config.parallel_opts = config.parallel_opts.with_num_threads(num_threads);
@@ -39,6 +109,12 @@ impl LTPServerOptions {
         if self.mcts {
             config.selected = WhichStrategy::MCTS;
         }
+        if self.eval_only {
+            config.selected = WhichStrategy::Greedy;
+        }
+        if let Some(strategy) = self.strategy {
+            config.selected = strategy;
+        }
         if self.ponder {
             config.parallel_opts = config.parallel_opts.with_background_pondering();
         }
@@ -48,6 +124,10 @@ impl LTPServerOptions {
         if let Some(table_size) = self.table_mb {
             config.search_opts.table_byte_size = table_size.checked_shl(20).unwrap();
         }
+        config.random_seed = self.seed;
+        if self.threads_movegen {
+            config.parallel_movegen = true;
+        }
         if self.verbose {
             config.search_opts = config.search_opts.verbose();
             config.mcts_opts = config.mcts_opts.verbose();
@@ -58,4 +138,23 @@ impl LTPServerOptions {
         
         config
     }
+
+    pub fn board_options(&self) -> BoardOptions {
+        let mut options = BoardOptions::new();
+
+        if self.canonical_openings {
+            options = options.with_canonical_openings();
+        }
+        if let Some(bound) = self.eval_clamp {
+            options = options.with_eval_clamp(bound);
+        }
+        if let Some(threshold) = self.eval_endgame_exact {
+            options = options.with_exact_endgame(threshold);
+        }
+        if self.position_hash {
+            options = options.with_position_keyed_table();
+        }
+
+        options
+    }
 }
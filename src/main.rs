@@ -1,12 +1,10 @@
-#![feature(never_type)]
-
 use std::time::Instant;
 
 use clap::Parser;
 use flexi_logger::{AdaptiveFormat, Logger, WriteMode};
 use lib_blits::prelude::*;
 
-fn main() -> Result<!> {
+fn main() -> Result<()> {
     // Initialize program options and environment.
     dotenvy::dotenv()?;
     let options = LTPServerOptions::parse();
@@ -23,10 +21,20 @@ fn main() -> Result<!> {
 
     // Serve LTP and the BLITS engine.
     let start_computing_piecemap = Instant::now();
-    let piecemap = Box::leak(Box::new(PieceMap::new()));
+    let piecemap = PieceMap::new();
     log::info!("ready in {:.2}s", (Instant::now() - start_computing_piecemap).as_secs_f64());
     
-    let Err(e) = LTPServer::new(options, piecemap).run();
-    log::error!("fatal error: {}", e);
-    Err(e)
+    let free_piecemap = options.no_leak_piecemap;
+    let result = LTPServer::new(options, &piecemap).and_then(|mut server| server.run());
+    if let Err(e) = &result {
+        log::error!("fatal error: {}", e);
+    }
+
+    // The server (and with it every board borrowing the piecemap) is gone by now, and with pondering off when the map
+    // is to be freed, so is any background search. Unless asked to free the piecemap for the sake of leak checkers,
+    // skip its teardown, since the process is about to exit anyway.
+    if !free_piecemap {
+        std::mem::forget(piecemap);
+    }
+    result
 }
@@ -0,0 +1,27 @@
+use std::{io::Write, process::{Command, Stdio}};
+
+/// Serves a short session under valgrind with `--no-leak-piecemap`, and fails if anything is definitely lost.
+///
+/// Needs `valgrind` on the path and takes a while, so run it explicitly with `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn no_leak_piecemap_exits_without_leaking() {
+    let dir = std::env::temp_dir().join(format!("blits-leak-check-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(".env"), "").unwrap(); // the binary expects a .env file
+
+    let mut child = Command::new("valgrind")
+        .args(["--leak-check=full", "--errors-for-leak-kinds=definite", "--error-exitcode=99"])
+        .arg(env!("CARGO_BIN_EXE_blits"))
+        .args(["--no-leak-piecemap", "--num-threads", "1", "--table-mb", "1"])
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("valgrind is not installed");
+    child.stdin.take().unwrap().write_all(b"newgame\nbestmove depth 1\nquit\n").unwrap();
+    let status = child.wait().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success(), "valgrind reported a leak or the server failed: {status}");
+}